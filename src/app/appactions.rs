@@ -18,6 +18,9 @@ impl RnApp {
         let action_new_window = gio::SimpleAction::new("new-window", None);
         self.add_action(&action_new_window);
 
+        let action_quick_add = gio::SimpleAction::new("quick-add", None);
+        self.add_action(&action_quick_add);
+
         action_color_scheme
             .bind_property("state", &self.style_manager(), "color-scheme")
             .transform_to(move |_, val: glib::Variant| {
@@ -65,6 +68,14 @@ impl RnApp {
                 app.new_appwindow_init_show();
             }
         ));
+
+        action_quick_add.connect_activate(clone!(
+            #[weak(rename_to = app)]
+            self,
+            move |_, _| {
+                app.show_quick_capture();
+            }
+        ));
     }
 
     // Accelerators / Keyboard Shortcuts
@@ -2,9 +2,19 @@
 mod appactions;
 
 // Imports
-use crate::{config, RnAppMenu, RnAppWindow, RnMainHeader, RnSidebar};
+use crate::utils::data_path;
+use crate::{
+    config, crypto, env, RnAppMenu, RnAppWindow, RnCalendarView, RnCompletedView,
+    RnFilterView, RnMainHeader, RnMatrixView, RnQuickCapture, RnScheduledView,
+    RnSearchView, RnSidebar, RnTaskBoard, RnTaskDetail, RnTodayView, RnTodo,
+};
 use adw::subclass::prelude::AdwApplicationImpl;
 use gtk::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use std::cell::Cell;
+use tracing::error;
+
+/// Opens the quick capture window instead of the main window.
+pub(crate) const QUICK_ADD_FLAG: &str = "quick-add";
 
 mod imp {
     use super::*;
@@ -12,20 +22,33 @@ mod imp {
     #[derive(Debug)]
     pub(crate) struct RnApp {
         pub(crate) app_settings: Option<gio::Settings>,
+        /// Set by `--quick-add` when this is the first instance
+        pub(crate) quick_capture_only: Cell<bool>,
     }
 
     impl Default for RnApp {
         fn default() -> Self {
+            // In portable mode the settings are stored in a keyfile next to the data
+            let settings_backend = env::portable_dir().map(|dir| {
+                gio::keyfile_settings_backend_new(
+                    &dir.join("settings.ini").to_string_lossy(),
+                    "/",
+                    None,
+                )
+            });
             let app_settings =
                 gio::SettingsSchemaSource::default().and_then(|schema_source| {
                     Some(gio::Settings::new_full(
                         &schema_source.lookup(config::APP_ID, true)?,
-                        None::<&gio::SettingsBackend>,
+                        settings_backend.as_ref(),
                         None,
                     ))
                 });
 
-            Self { app_settings }
+            Self {
+                app_settings,
+                quick_capture_only: Cell::new(false),
+            }
         }
     }
 
@@ -51,10 +74,31 @@ mod imp {
         fn activate(&self) {
             self.parent_activate();
 
+            if self.quick_capture_only.take() {
+                self.obj().show_quick_capture();
+                return;
+            }
             // init and show a new window
             self.new_appwindow_init_show(None);
         }
 
+        fn handle_local_options(&self, options: &glib::VariantDict) -> glib::ExitCode {
+            if options.contains(QUICK_ADD_FLAG) {
+                let obj = self.obj();
+                if let Err(e) = obj.register(None::<&gio::Cancellable>) {
+                    error!("Failed to register the app, Err: {e:?}");
+                    return glib::ExitCode::FAILURE;
+                }
+                if obj.is_remote() {
+                    // The running instance opens the window, this one is done
+                    obj.activate_action(QUICK_ADD_FLAG, None);
+                    return glib::ExitCode::SUCCESS;
+                }
+                self.quick_capture_only.set(true);
+            }
+            self.parent_handle_local_options(options)
+        }
+
         fn open(&self, files: &[gio::File], hint: &str) {
             self.parent_open(files, hint);
 
@@ -90,6 +134,15 @@ mod imp {
             RnAppMenu::static_type();
             RnMainHeader::static_type();
             RnSidebar::static_type();
+            RnTodayView::static_type();
+            RnScheduledView::static_type();
+            RnCompletedView::static_type();
+            RnFilterView::static_type();
+            RnSearchView::static_type();
+            RnCalendarView::static_type();
+            RnMatrixView::static_type();
+            RnTaskBoard::static_type();
+            RnTaskDetail::static_type();
         }
 
         /// Initializes and shows a new app window
@@ -127,12 +180,31 @@ impl Default for RnApp {
 
 impl RnApp {
     pub(crate) fn new() -> Self {
-        glib::Object::builder()
+        let app: Self = glib::Object::builder()
             .property("application-id", config::APP_ID)
             .property("resource-base-path", config::APP_IDPATH)
             .property("flags", gio::ApplicationFlags::HANDLES_OPEN)
             .property("register-session", true)
-            .build()
+            .build();
+
+        // Evaluated in `env::setup_portable()`, only registered here so that it is accepted and listed in `--help`
+        app.add_main_option(
+            env::PORTABLE_FLAG,
+            glib::Char::from(env::PORTABLE_FLAG_SHORT),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::None,
+            "Store data, backups and settings next to the executable",
+            None,
+        );
+        app.add_main_option(
+            QUICK_ADD_FLAG,
+            glib::Char::from(b'q'),
+            glib::OptionFlags::NONE,
+            glib::OptionArg::None,
+            "Open a small window to add a task to the Inbox",
+            None,
+        );
+        app
     }
 
     /// Returns the app settings, if the schema is found in the compiled gschema. If not, returns None.
@@ -149,4 +221,32 @@ impl RnApp {
     pub(crate) fn new_appwindow_init_show(&self) {
         self.imp().new_appwindow_init_show(None);
     }
+
+    /// The tasks of a main window, if one is open and not locked.
+    pub(crate) fn unlocked_todo(&self) -> Option<RnTodo> {
+        self.windows()
+            .into_iter()
+            .filter_map(|window| window.downcast::<RnAppWindow>().ok())
+            .map(|appwindow| appwindow.todo())
+            .find(|todo| !todo.is_locked())
+    }
+
+    /// Opens the quick capture window, which adds a task without showing the main window.
+    ///
+    /// Encrypted data can only be added to once it is unlocked, so the main window is
+    /// shown instead while it is locked.
+    pub(crate) fn show_quick_capture(&self) {
+        if self.unlocked_todo().is_none() && crypto::is_encrypted_file(data_path()) {
+            match self
+                .windows()
+                .into_iter()
+                .find_map(|window| window.downcast::<RnAppWindow>().ok())
+            {
+                Some(appwindow) => appwindow.present(),
+                None => self.new_appwindow_init_show(),
+            }
+            return;
+        }
+        RnQuickCapture::new(self).present();
+    }
 }
@@ -40,6 +40,29 @@ impl RnAppWindow {
         let action_righthanded =
             gio::PropertyAction::new("righthanded", self, "righthanded");
         self.add_action(&action_righthanded);
+        let action_lock = gio::SimpleAction::new("lock", None);
+        self.add_action(&action_lock);
+        let action_set_passphrase = gio::SimpleAction::new("set-passphrase", None);
+        self.add_action(&action_set_passphrase);
+        let action_remove_passphrase =
+            gio::SimpleAction::new("remove-passphrase", None);
+        self.add_action(&action_remove_passphrase);
+        let action_new_smart_filter = gio::SimpleAction::new("new-smart-filter", None);
+        self.add_action(&action_new_smart_filter);
+        let action_search = gio::SimpleAction::new("search", None);
+        self.add_action(&action_search);
+        let action_undo = gio::SimpleAction::new("undo", None);
+        action_undo.set_enabled(false);
+        self.add_action(&action_undo);
+        let action_redo = gio::SimpleAction::new("redo", None);
+        action_redo.set_enabled(false);
+        self.add_action(&action_redo);
+        let action_clipboard_copy = gio::SimpleAction::new("clipboard-copy", None);
+        self.add_action(&action_clipboard_copy);
+        let action_clipboard_cut = gio::SimpleAction::new("clipboard-cut", None);
+        self.add_action(&action_clipboard_cut);
+        let action_clipboard_paste = gio::SimpleAction::new("clipboard-paste", None);
+        self.add_action(&action_clipboard_paste);
         // Open settings
         action_open_settings.connect_activate(clone!(
             #[weak(rename_to = appwindow)]
@@ -84,6 +107,125 @@ impl RnAppWindow {
             }
         ));
 
+        // Lock
+        action_lock.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.lock();
+            }
+        ));
+
+        // Set or change the passphrase
+        action_set_passphrase.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_set_passphrase(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Remove the passphrase
+        action_remove_passphrase.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if !appwindow.todo().is_encrypted() {
+                    return;
+                }
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::dialog_remove_passphrase(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // New smart filter
+        action_new_smart_filter.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.filter_view().show_filter(None);
+                appwindow
+                    .views_stack()
+                    .set_visible_child_name("filter_page");
+            }
+        ));
+
+        // Search
+        action_search.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                appwindow.search_view().start_search();
+            }
+        ));
+
+        // Undo and redo, text fields keep undoing their own text
+        action_undo.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if !appwindow.activate_focused_text_action("text.undo") {
+                    appwindow.todo().undo();
+                }
+            }
+        ));
+        action_redo.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if !appwindow.activate_focused_text_action("text.redo") {
+                    appwindow.todo().redo();
+                }
+            }
+        ));
+
+        // Clipboard, of the selected tasks unless a text field is focused
+        action_clipboard_copy.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if !appwindow.activate_focused_text_action("clipboard.copy") {
+                    appwindow.todo().copy_selected_tasks();
+                }
+            }
+        ));
+        action_clipboard_cut.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if !appwindow.activate_focused_text_action("clipboard.cut") {
+                    appwindow.todo().cut_selected_tasks();
+                }
+            }
+        ));
+        action_clipboard_paste.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                if appwindow.activate_focused_text_action("clipboard.paste") {
+                    return;
+                }
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        appwindow.todo().paste_tasks().await;
+                    }
+                ));
+            }
+        ));
+
         // Open App Menu
         action_open_appmenu.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -128,16 +270,35 @@ impl RnAppWindow {
         action_devel_menu.set_enabled(false);
     }
 
+    /// Activates the action of the focused text field, returns whether there is one.
+    fn activate_focused_text_action(&self, action_name: &str) -> bool {
+        let Some(focus) = GtkWindowExt::focus(self) else {
+            return false;
+        };
+        if !(focus.is::<gtk::Text>() || focus.is::<gtk::TextView>()) {
+            return false;
+        }
+        if let Err(e) = focus.activate_action(action_name, None) {
+            error!("Activating `{action_name}` of the text field failed, Err: {e:?}");
+        }
+        true
+    }
+
     pub(crate) fn setup_action_accels(&self) {
         let app = self.app();
 
         app.set_accels_for_action("win.fullscreen", &["F11"]);
         app.set_accels_for_action("win.keyboard-shortcuts", &["<Ctrl>question"]);
         app.set_accels_for_action("win.open-appmenu", &["F10"]);
+        app.set_accels_for_action("win.lock", &["<Ctrl>l"]);
+        app.set_accels_for_action("win.search", &["<Ctrl>f"]);
+        app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
+        app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
+        app.set_accels_for_action("win.clipboard-copy", &["<Ctrl>c"]);
+        app.set_accels_for_action("win.clipboard-cut", &["<Ctrl>x"]);
+        app.set_accels_for_action("win.clipboard-paste", &["<Ctrl>v"]);
 
-        app.set_accels_for_action("win.filter('All')", &["<Ctrl>a"]);
-        app.set_accels_for_action("win.filter('Open')", &["<Ctrl>o"]);
-        app.set_accels_for_action("win.filter('Done')", &["<Ctrl>d"]);
+        app.set_accels_for_action("collection.show-completed", &["<Ctrl>h"]);
         // shortcuts for devel build
         if config::PROFILE.to_lowercase().as_str() == "devel" {
             app.set_accels_for_action("win.visual-debug", &["<Ctrl><Shift>v"]);
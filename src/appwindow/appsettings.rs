@@ -28,19 +28,17 @@ impl RnAppWindow {
                 }
             }
         ));
-        // filter
-        let action_filter = app_settings.create_action("filter");
-        self.add_action(&action_filter);
-        app_settings.connect_changed(
-            Some("filter"),
-            clone!(
-                #[weak(rename_to = window)]
-                self,
-                move |_, _| {
-                    window.todo().set_filter();
-                }
-            ),
-        );
+        // auto-lock
+        let action_auto_lock = app_settings.create_action("auto-lock-minutes");
+        self.add_action(&action_auto_lock);
+        // date language of quick add
+        let action_quick_add_language =
+            app_settings.create_action("quick-add-language");
+        self.add_action(&action_quick_add_language);
+        // missed runs of scheduled templates
+        let action_missed_runs_limit = app_settings.create_action("missed-runs-limit");
+        self.add_action(&action_missed_runs_limit);
+
         app_settings
             .bind("sidebar-show", &self.overlay_split_view(), "show-sidebar")
             .get_no_changes()
@@ -1,14 +1,19 @@
 // Imports
-use crate::{config, RnMainHeader, RnSidebar, RnTodo};
+use crate::{
+    config, RnCalendarView, RnCompletedView, RnFilterView, RnMainHeader, RnMatrixView,
+    RnScheduledView, RnSearchView, RnSidebar, RnTodayView, RnTodo,
+};
 use adw::{prelude::*, subclass::prelude::*, OverlaySplitView, ViewStack};
 use gtk::{gdk, glib, glib::clone, CompositeTemplate, CssProvider, PackType};
 use once_cell::sync::Lazy;
 use std::cell::Cell;
 use std::rc::Rc;
+use std::time::Instant;
 #[derive(Debug, CompositeTemplate)]
 #[template(resource = "/com/github/linruohan/mytool/ui/appwindow.ui")]
 pub(crate) struct RnAppWindow {
     pub(crate) righthanded: Cell<bool>,
+    pub(crate) last_activity: Cell<Instant>,
 
     #[template_child]
     pub(crate) view_stack: TemplateChild<ViewStack>,
@@ -22,12 +27,35 @@ pub(crate) struct RnAppWindow {
     pub(crate) views_stack: TemplateChild<ViewStack>,
     #[template_child]
     pub(crate) todo: TemplateChild<RnTodo>,
+    #[template_child]
+    pub(crate) today_view: TemplateChild<RnTodayView>,
+    #[template_child]
+    pub(crate) scheduled_view: TemplateChild<RnScheduledView>,
+    #[template_child]
+    pub(crate) completed_view: TemplateChild<RnCompletedView>,
+    #[template_child]
+    pub(crate) filter_view: TemplateChild<RnFilterView>,
+    #[template_child]
+    pub(crate) search_view: TemplateChild<RnSearchView>,
+    #[template_child]
+    pub(crate) calendar_view: TemplateChild<RnCalendarView>,
+    #[template_child]
+    pub(crate) matrix_view: TemplateChild<RnMatrixView>,
+    #[template_child]
+    pub(crate) toast_overlay: TemplateChild<adw::ToastOverlay>,
+    #[template_child]
+    pub(crate) unlock_entry: TemplateChild<adw::PasswordEntryRow>,
+    #[template_child]
+    pub(crate) unlock_error_label: TemplateChild<gtk::Label>,
+    #[template_child]
+    pub(crate) unlock_button: TemplateChild<gtk::Button>,
 }
 
 impl Default for RnAppWindow {
     fn default() -> Self {
         Self {
             righthanded: Cell::new(true),
+            last_activity: Cell::new(Instant::now()),
 
             view_stack: TemplateChild::<ViewStack>::default(),
             main_header: TemplateChild::<RnMainHeader>::default(),
@@ -35,6 +63,17 @@ impl Default for RnAppWindow {
             sidebar: TemplateChild::<RnSidebar>::default(),
             views_stack: TemplateChild::<ViewStack>::default(),
             todo: TemplateChild::<RnTodo>::default(),
+            today_view: TemplateChild::<RnTodayView>::default(),
+            scheduled_view: TemplateChild::<RnScheduledView>::default(),
+            completed_view: TemplateChild::<RnCompletedView>::default(),
+            filter_view: TemplateChild::<RnFilterView>::default(),
+            search_view: TemplateChild::<RnSearchView>::default(),
+            calendar_view: TemplateChild::<RnCalendarView>::default(),
+            matrix_view: TemplateChild::<RnMatrixView>::default(),
+            toast_overlay: TemplateChild::<adw::ToastOverlay>::default(),
+            unlock_entry: TemplateChild::<adw::PasswordEntryRow>::default(),
+            unlock_error_label: TemplateChild::<gtk::Label>::default(),
+            unlock_button: TemplateChild::<gtk::Button>::default(),
         }
     }
 }
@@ -62,6 +101,13 @@ impl ObjectSubclass for RnAppWindow {
                 window.views_stack().set_visible_child_name("reminder_page");
             },
         );
+        klass.install_action_async(
+            "win.matrix-view",
+            None,
+            |window, _, _| async move {
+                window.views_stack().set_visible_child_name("matrix_page");
+            },
+        );
     }
 
     fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -0,0 +1,137 @@
+// Imports
+use crate::appwindow::RnAppWindow;
+use crate::crypto;
+use crate::utils::data_path;
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gio, glib, glib::clone, EventControllerLegacy};
+use std::time::{Duration, Instant};
+
+/// How often the inactivity timeout is checked.
+const AUTO_LOCK_CHECK_INTERVAL_SECS: u32 = 15;
+
+impl RnAppWindow {
+    /// Loads the data, or shows the unlock page first when the data file is encrypted.
+    pub(crate) fn load_data(&self) {
+        if crypto::is_encrypted_file(data_path()) {
+            self.todo().set_locked();
+            self.show_unlock_page();
+        } else {
+            self.todo().restore_data();
+        }
+    }
+
+    pub(crate) fn setup_lock(&self) {
+        let imp = self.imp();
+
+        imp.unlock_entry.connect_entry_activated(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_| {
+                appwindow.try_unlock();
+            }
+        ));
+        imp.unlock_button.connect_clicked(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_| {
+                appwindow.try_unlock();
+            }
+        ));
+
+        // Any input counts as activity and postpones the auto-lock
+        let activity_controller = EventControllerLegacy::builder()
+            .name("activity_controller")
+            .propagation_phase(gtk::PropagationPhase::Capture)
+            .build();
+        activity_controller.connect_event(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, _| {
+                appwindow.imp().last_activity.set(Instant::now());
+                glib::Propagation::Proceed
+            }
+        ));
+        self.add_controller(activity_controller);
+
+        glib::timeout_add_seconds_local(
+            AUTO_LOCK_CHECK_INTERVAL_SECS,
+            clone!(
+                #[weak(rename_to=appwindow)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    appwindow.check_auto_lock();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    /// Saves and hides the data, until the passphrase is entered again.
+    pub(crate) fn lock(&self) {
+        let todo = self.todo();
+        if !todo.is_encrypted() || todo.is_locked() {
+            return;
+        }
+        todo.lock();
+        self.show_unlock_page();
+    }
+
+    fn show_unlock_page(&self) {
+        let imp = self.imp();
+        imp.unlock_entry.set_text("");
+        imp.unlock_error_label.set_visible(false);
+        self.set_passphrase_actions_enabled(false);
+        self.view_stack().set_visible_child_name("unlock_page");
+        imp.unlock_entry.grab_focus();
+    }
+
+    fn try_unlock(&self) {
+        let imp = self.imp();
+        let passphrase = imp.unlock_entry.text();
+
+        match self.todo().unlock(&passphrase) {
+            Ok(()) => {
+                imp.unlock_entry.set_text("");
+                imp.unlock_error_label.set_visible(false);
+                imp.last_activity.set(Instant::now());
+                self.set_passphrase_actions_enabled(true);
+                self.view_stack().set_visible_child_name("main_page");
+            }
+            Err(e) => {
+                imp.unlock_error_label.set_label(&e.to_string());
+                imp.unlock_error_label.set_visible(true);
+                imp.unlock_entry.grab_focus();
+            }
+        }
+    }
+
+    /// The passphrase can't be changed while the data isn't loaded.
+    fn set_passphrase_actions_enabled(&self, enabled: bool) {
+        for name in ["set-passphrase", "remove-passphrase"] {
+            if let Some(action) =
+                self.lookup_action(name).and_downcast::<gio::SimpleAction>()
+            {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    fn check_auto_lock(&self) {
+        let Some(app_settings) = self.app().app_settings() else {
+            return;
+        };
+        let minutes = app_settings.uint("auto-lock-minutes");
+        if minutes == 0 {
+            return;
+        }
+
+        let idle = self.imp().last_activity.get().elapsed();
+        if idle >= Duration::from_secs(u64::from(minutes) * 60) {
+            self.lock();
+        }
+    }
+}
@@ -2,6 +2,7 @@
 mod actions;
 mod appsettings;
 mod imp;
+mod lock;
 
 // Imports
 use crate::{config, RnApp, RnSidebar};
@@ -55,16 +56,33 @@ impl RnAppWindow {
     pub(crate) fn views_stack(&self) -> ViewStack {
         self.imp().views_stack.get()
     }
+    pub(crate) fn filter_view(&self) -> crate::RnFilterView {
+        self.imp().filter_view.get()
+    }
+    pub(crate) fn search_view(&self) -> crate::RnSearchView {
+        self.imp().search_view.get()
+    }
+    pub(crate) fn toast_overlay(&self) -> adw::ToastOverlay {
+        self.imp().toast_overlay.get()
+    }
     /// Must be called after application is associated with the window else the init will panic
     pub(crate) fn init(&self) {
         let imp = self.imp();
 
         imp.sidebar.get().init(self);
         imp.main_header.get().init(self);
+        imp.today_view.get().init(self);
+        imp.scheduled_view.get().init(self);
+        imp.completed_view.get().init(self);
+        imp.filter_view.get().init(self);
+        imp.search_view.get().init(self);
+        imp.calendar_view.get().init(self);
+        imp.matrix_view.get().init(self);
         // actions and settings AFTER widget inits
         self.setup_icon_theme();
         self.setup_actions();
         self.setup_action_accels();
+        self.setup_lock();
 
         if !self.app().settings_schema_found() {
             // Display an error toast if settings schema could not be found
@@ -81,6 +99,7 @@ impl RnAppWindow {
         }
 
         // Anything that needs to be done right before showing the appwindow
+        self.load_data();
 
         // Set undo / redo as not sensitive as default - setting it in .ui file did not work for some reason
 
@@ -2,9 +2,16 @@ use std::cell::RefCell;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
+use glib::subclass::Signal;
 use glib::Properties;
-use gtk::{gio, glib};
-use std::cell::OnceCell;
+use glib::SignalHandlerId;
+use gtk::{gio, glib, glib::clone};
+use once_cell::sync::Lazy;
+use std::cell::{Cell, OnceCell};
+use std::collections::HashMap;
+
+use super::ViewOptions;
+use crate::task_object::TaskObject;
 
 // ANCHOR: collection_object
 // Object holding the state
@@ -15,6 +22,12 @@ pub struct CollectionObject {
     pub title: RefCell<String>,
     #[property(get, set)]
     pub tasks: OnceCell<gio::ListStore>,
+    pub view_options: Cell<ViewOptions>,
+    pub inbox: Cell<bool>,
+    /// Sections that were added to the board, see `CollectionObject::sections`
+    pub sections: RefCell<Vec<String>>,
+    /// The handlers connected to each task, disconnected when it is removed
+    pub task_handlers: RefCell<HashMap<TaskObject, Vec<SignalHandlerId>>>,
 }
 
 // The central trait for subclassing a GObject
@@ -27,4 +40,36 @@ impl ObjectSubclass for CollectionObject {
 
 // Trait shared by all GObjects
 #[glib::derived_properties]
-impl ObjectImpl for CollectionObject {}
+impl ObjectImpl for CollectionObject {
+    fn signals() -> &'static [Signal] {
+        static SIGNALS: Lazy<Vec<Signal>> =
+            Lazy::new(|| vec![Signal::builder("tasks-changed").build()]);
+        SIGNALS.as_ref()
+    }
+
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        // Emit `tasks-changed` when tasks are added, removed or modified
+        let obj = self.obj();
+        let tasks = obj.tasks();
+        for task_object in tasks.iter::<TaskObject>().filter_map(Result::ok) {
+            obj.watch_task(&task_object);
+        }
+        tasks.connect_items_changed(clone!(
+            #[weak]
+            obj,
+            move |tasks, position, removed, added| {
+                if removed > 0 {
+                    obj.unwatch_removed_tasks();
+                }
+                for task_object in (position..position + added)
+                    .filter_map(|i| tasks.item(i).and_downcast::<TaskObject>())
+                {
+                    obj.watch_task(&task_object);
+                }
+                obj.emit_by_name::<()>("tasks-changed", &[]);
+            }
+        ));
+    }
+}
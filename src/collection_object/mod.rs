@@ -3,10 +3,15 @@ mod imp;
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::Object;
-use gtk::{gio, glib};
+use gtk::{gio, glib, glib::clone};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-use crate::task_object::{TaskData, TaskObject};
+use crate::task_object::{TaskData, TaskDue, TaskObject, TaskRecurrence};
+use crate::template::TemplateData;
+
+/// The title of the Inbox, see [CollectionObject::is_inbox].
+pub const INBOX_TITLE: &str = "Inbox";
 
 glib::wrapper! {
     pub struct CollectionObject(ObjectSubclass<imp::CollectionObject>);
@@ -21,6 +26,18 @@ impl CollectionObject {
             .build()
     }
 
+    pub fn new_inbox() -> Self {
+        let collection = Self::new(INBOX_TITLE, gio::ListStore::new::<TaskObject>());
+        collection.imp().inbox.set(true);
+        collection
+    }
+
+    /// Whether this is the Inbox, which receives tasks that are added without a target
+    /// collection and can't be deleted.
+    pub fn is_inbox(&self) -> bool {
+        self.imp().inbox.get()
+    }
+
     pub fn to_collection_data(&self) -> CollectionData {
         let title = self.imp().title.borrow().clone();
         let tasks_data = self
@@ -29,7 +46,151 @@ impl CollectionObject {
             .filter_map(Result::ok)
             .map(|task_object| task_object.task_data())
             .collect();
-        CollectionData { title, tasks_data }
+        CollectionData {
+            title,
+            tasks_data,
+            view_options: self.view_options(),
+            inbox: self.is_inbox(),
+            sections: self.imp().sections.borrow().clone(),
+        }
+    }
+
+    pub fn view_options(&self) -> ViewOptions {
+        self.imp().view_options.get()
+    }
+
+    pub fn set_view_options(&self, view_options: ViewOptions) {
+        self.imp().view_options.set(view_options);
+    }
+
+    /// The sections of the board, in the order of its columns.
+    ///
+    /// Includes sections that only tasks name, after the sections that were added.
+    pub fn sections(&self) -> Vec<String> {
+        let mut sections = self.imp().sections.borrow().clone();
+        for task_object in self.tasks().iter::<TaskObject>().filter_map(Result::ok) {
+            let section = task_object.section();
+            if !section.is_empty() && !sections.contains(&section) {
+                sections.push(section);
+            }
+        }
+        sections
+    }
+
+    /// Adds an empty section as the last column of the board.
+    pub fn add_section(&self, section: &str) {
+        let mut sections = self.sections();
+        if section.is_empty() || sections.iter().any(|s| s == section) {
+            return;
+        }
+        sections.push(section.to_string());
+        self.imp().sections.replace(sections);
+        self.emit_by_name::<()>("tasks-changed", &[]);
+    }
+
+    /// Removes the task, if it belongs to this collection.
+    pub fn remove_task(&self, task_object: &TaskObject) {
+        let tasks = self.tasks();
+        if let Some(position) = tasks.find(task_object) {
+            tasks.remove(position);
+        }
+    }
+
+    fn watch_task(&self, task_object: &TaskObject) {
+        if self.imp().task_handlers.borrow().contains_key(task_object) {
+            return;
+        }
+        let notify_handler = task_object.connect_notify_local(
+            None,
+            clone!(
+                #[weak(rename_to = collection)]
+                self,
+                move |_, _| {
+                    collection.emit_by_name::<()>("tasks-changed", &[]);
+                }
+            ),
+        );
+        let completed_handler = task_object.connect_completed_notify(clone!(
+            #[weak(rename_to = collection)]
+            self,
+            move |task_object| {
+                if task_object.is_completed() {
+                    collection.repeat_task(task_object);
+                }
+            }
+        ));
+        self.imp()
+            .task_handlers
+            .borrow_mut()
+            .insert(task_object.clone(), vec![notify_handler, completed_handler]);
+    }
+
+    /// Disconnects from the tasks that aren't in the collection anymore.
+    fn unwatch_removed_tasks(&self) {
+        let tasks: HashSet<TaskObject> = self
+            .tasks()
+            .iter::<TaskObject>()
+            .filter_map(Result::ok)
+            .collect();
+        self.imp()
+            .task_handlers
+            .borrow_mut()
+            .retain(|task_object, handler_ids| {
+                if tasks.contains(task_object) {
+                    return true;
+                }
+                for handler_id in handler_ids.drain(..) {
+                    task_object.disconnect(handler_id);
+                }
+                false
+            });
+    }
+
+    /// Adds the next occurrence of a completed recurring task below it.
+    ///
+    /// The completed task stops recurring, so that it stays in the completion history.
+    /// Nothing happens unless the task is in this collection at completion time.
+    fn repeat_task(&self, task_object: &TaskObject) {
+        let (Some(recurrence), Some(due)) =
+            (task_object.recurrence(), task_object.due())
+        else {
+            return;
+        };
+        let Some(next_date) = recurrence.next(due.date) else {
+            return;
+        };
+        let tasks = self.tasks();
+        let Some(position) = tasks.find(task_object) else {
+            return;
+        };
+        task_object.set_recurrence(None::<TaskRecurrence>);
+
+        let next_task = TaskObject::new(false, task_object.content());
+        next_task.set_notes(task_object.notes());
+        next_task.set_priority(task_object.priority());
+        next_task.set_section(task_object.section());
+        next_task.set_due(Some(TaskDue::new(next_date, due.time)));
+        next_task.set_recurrence(Some(recurrence));
+        // The checklist starts over, attachments are kept
+        let mut subtasks = task_object.subtasks();
+        for subtask in &mut subtasks.0 {
+            subtask.completed = false;
+        }
+        next_task.set_subtasks(subtasks);
+        next_task.set_attachments(task_object.attachments());
+
+        tasks.insert(position + 1, &next_task);
+    }
+
+    pub fn connect_tasks_changed<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "tasks-changed",
+            false,
+            glib::closure_local!(move |collection: &Self| f(collection)),
+        )
     }
 
     pub fn from_collection_data(collection_data: CollectionData) -> Self {
@@ -43,7 +204,11 @@ impl CollectionObject {
         let tasks = gio::ListStore::new::<TaskObject>();
         tasks.extend_from_slice(&tasks_to_extend);
 
-        Self::new(&title, tasks)
+        let collection = Self::new(&title, tasks);
+        collection.set_view_options(collection_data.view_options);
+        collection.imp().inbox.set(collection_data.inbox);
+        collection.imp().sections.replace(collection_data.sections);
+        collection
     }
 }
 // ANCHOR_END: impl
@@ -53,5 +218,278 @@ impl CollectionObject {
 pub struct CollectionData {
     pub title: String,
     pub tasks_data: Vec<TaskData>,
+    #[serde(default)]
+    pub view_options: ViewOptions,
+    #[serde(default)]
+    pub inbox: bool,
+    #[serde(default)]
+    pub sections: Vec<String>,
 }
 // ANCHOR_END: collection_data
+
+/// The contents of the data file.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "StoredData")]
+pub struct TodoData {
+    pub collections: Vec<CollectionData>,
+    pub templates: Vec<TemplateData>,
+}
+
+/// Data files of older versions only hold the collections.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredData {
+    Full {
+        collections: Vec<CollectionData>,
+        #[serde(default)]
+        templates: Vec<TemplateData>,
+    },
+    Collections(Vec<CollectionData>),
+}
+
+impl From<StoredData> for TodoData {
+    fn from(stored_data: StoredData) -> Self {
+        let mut todo_data = match stored_data {
+            StoredData::Full {
+                collections,
+                templates,
+            } => Self {
+                collections,
+                templates,
+            },
+            StoredData::Collections(collections) => Self {
+                collections,
+                templates: Vec::new(),
+            },
+        };
+        adopt_inbox(&mut todo_data.collections);
+        todo_data
+    }
+}
+
+/// Data of versions without the Inbox might already have a collection with its title,
+/// it becomes the Inbox instead of getting a second one next to it.
+fn adopt_inbox(collections: &mut [CollectionData]) {
+    if collections
+        .iter()
+        .any(|collection_data| collection_data.inbox)
+    {
+        return;
+    }
+    if let Some(collection_data) = collections
+        .iter_mut()
+        .find(|collection_data| collection_data.title == INBOX_TITLE)
+    {
+        collection_data.inbox = true;
+    }
+}
+
+/// How the tasks of a collection are listed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewOptions {
+    pub sort: TaskSort,
+    pub group: TaskGroup,
+    pub show_completed: bool,
+    pub layout: TaskLayout,
+    pub board_columns: BoardColumns,
+}
+
+impl Default for ViewOptions {
+    fn default() -> Self {
+        Self {
+            sort: TaskSort::default(),
+            group: TaskGroup::default(),
+            show_completed: true,
+            layout: TaskLayout::default(),
+            board_columns: BoardColumns::default(),
+        }
+    }
+}
+
+/// Whether the tasks are shown as a list or as cards on a board.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskLayout {
+    #[default]
+    List,
+    Board,
+}
+
+impl TaskLayout {
+    pub const ALL: [Self; 2] = [Self::List, Self::Board];
+
+    /// The name that is used as the target of the `collection.layout` action.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Board => "board",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|layout| layout.id() == id)
+    }
+}
+
+/// What the columns of the board stand for.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BoardColumns {
+    /// One column per section, after a column for tasks without a section
+    #[default]
+    Section,
+    /// A column for open and one for completed tasks
+    Status,
+}
+
+impl BoardColumns {
+    pub const ALL: [Self; 2] = [Self::Section, Self::Status];
+
+    /// The name that is used as the target of the `collection.board-columns` action.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Section => "section",
+            Self::Status => "status",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|columns| columns.id() == id)
+    }
+}
+
+/// The order of the tasks within their group.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskSort {
+    /// The order the tasks were added or arranged in
+    #[default]
+    Manual,
+    Due,
+    Priority,
+    Alphabetical,
+    /// Newest first
+    Created,
+}
+
+impl TaskSort {
+    pub const ALL: [Self; 5] = [
+        Self::Manual,
+        Self::Due,
+        Self::Priority,
+        Self::Alphabetical,
+        Self::Created,
+    ];
+
+    /// The name that is used as the target of the `collection.sort` action.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Due => "due",
+            Self::Priority => "priority",
+            Self::Alphabetical => "alphabetical",
+            Self::Created => "created",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.id() == id)
+    }
+}
+
+/// What the tasks are grouped by, each group is listed under a header.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskGroup {
+    #[default]
+    None,
+    Section,
+    Due,
+    Priority,
+    /// The first `#tag` of the content
+    Label,
+}
+
+impl TaskGroup {
+    pub const ALL: [Self; 5] = [
+        Self::None,
+        Self::Section,
+        Self::Due,
+        Self::Priority,
+        Self::Label,
+    ];
+
+    /// The name that is used as the target of the `collection.group` action.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Section => "section",
+            Self::Due => "due",
+            Self::Priority => "priority",
+            Self::Label => "label",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|group| group.id() == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles_and_inbox(json: &str) -> Vec<(String, bool)> {
+        let todo_data: TodoData = serde_json::from_str(json).unwrap();
+        todo_data
+            .collections
+            .into_iter()
+            .map(|collection_data| (collection_data.title, collection_data.inbox))
+            .collect()
+    }
+
+    #[test]
+    fn collection_titled_inbox_becomes_the_inbox() {
+        assert_eq!(
+            titles_and_inbox(
+                r#"[
+                    {"title": "Work", "tasks_data": []},
+                    {"title": "Inbox", "tasks_data": []},
+                    {"title": "Inbox", "tasks_data": []}
+                ]"#
+            ),
+            [
+                (String::from("Work"), false),
+                (String::from("Inbox"), true),
+                (String::from("Inbox"), false),
+            ]
+        );
+        assert_eq!(
+            titles_and_inbox(
+                r#"{"collections": [{"title": "Inbox", "tasks_data": []}]}"#
+            ),
+            [(String::from("Inbox"), true)]
+        );
+    }
+
+    #[test]
+    fn existing_inbox_is_kept() {
+        assert_eq!(
+            titles_and_inbox(
+                r#"{"collections": [
+                    {"title": "Inbox", "tasks_data": []},
+                    {"title": "Inbox", "tasks_data": [], "inbox": true}
+                ]}"#
+            ),
+            [
+                (String::from("Inbox"), false),
+                (String::from("Inbox"), true),
+            ]
+        );
+        assert_eq!(
+            titles_and_inbox(r#"[{"title": "inbox", "tasks_data": []}]"#),
+            [(String::from("inbox"), false)]
+        );
+    }
+}
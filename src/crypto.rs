@@ -0,0 +1,95 @@
+// Imports
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::path::Path;
+
+/// Marks the start of an encrypted data file.
+const MAGIC: &[u8; 8] = b"RNTDENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+/// A key derived from the user passphrase, together with the salt it was derived with.
+///
+/// Kept in memory while the data is unlocked, so that saving does not need to derive it again.
+pub(crate) struct DataKey {
+    salt: [u8; SALT_LEN],
+    key: Key,
+}
+
+impl std::fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataKey").finish_non_exhaustive()
+    }
+}
+
+impl DataKey {
+    /// Derives a new key with a fresh random salt.
+    pub(crate) fn new(passphrase: &str) -> anyhow::Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> anyhow::Result<Self> {
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| {
+                anyhow::anyhow!("Deriving key from passphrase failed, Err: {e}")
+            })?;
+        Ok(Self { salt, key })
+    }
+
+    /// Checks if the passphrase derives to this key.
+    pub(crate) fn matches(&self, passphrase: &str) -> bool {
+        Self::derive(passphrase, self.salt).is_ok_and(|other| other.key == self.key)
+    }
+
+    pub(crate) fn encrypt(&self, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(&self.key);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| anyhow::anyhow!("Encrypting data failed, Err: {e}"))?;
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+}
+
+/// Checks if the bytes start with the header of an encrypted file.
+pub(crate) fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_LEN && bytes.starts_with(MAGIC)
+}
+
+/// Checks if the file at the path exists and is encrypted.
+pub(crate) fn is_encrypted_file(path: impl AsRef<Path>) -> bool {
+    std::fs::read(path).is_ok_and(|bytes| is_encrypted(&bytes))
+}
+
+/// Decrypts bytes that were written by [DataKey::encrypt].
+///
+/// Returns the plaintext and the key, so that the data can be encrypted again with the same passphrase.
+pub(crate) fn decrypt(
+    bytes: &[u8],
+    passphrase: &str,
+) -> anyhow::Result<(Vec<u8>, DataKey)> {
+    if !is_encrypted(bytes) {
+        return Err(anyhow::anyhow!("Data is not encrypted."));
+    }
+    let (salt, rest) = bytes[MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let data_key = DataKey::derive(passphrase, salt.try_into()?)?;
+    let plaintext = XChaCha20Poly1305::new(&data_key.key)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted data."))?;
+    Ok((plaintext, data_key))
+}
@@ -148,6 +148,82 @@ pub(crate) async fn dialog_trash_file(
         }
     }
 }
+/// Sets a new passphrase for the data file, asking for the current one when it is already encrypted.
+pub(crate) async fn dialog_set_passphrase(appwindow: &RnAppWindow) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_set_passphrase").unwrap();
+    let current_entry: adw::PasswordEntryRow =
+        builder.object("set_passphrase_current_entry").unwrap();
+    let new_entry: adw::PasswordEntryRow =
+        builder.object("set_passphrase_new_entry").unwrap();
+    let confirm_entry: adw::PasswordEntryRow =
+        builder.object("set_passphrase_confirm_entry").unwrap();
+    let todo = appwindow.todo();
+
+    current_entry.set_visible(todo.is_encrypted());
+    let update_response = clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        new_entry,
+        #[weak]
+        confirm_entry,
+        move || {
+            let valid = !new_entry.text().is_empty()
+                && new_entry.text() == confirm_entry.text();
+            dialog.set_response_enabled("set", valid);
+        }
+    );
+    new_entry.connect_changed(clone!(
+        #[strong]
+        update_response,
+        move |_| update_response()
+    ));
+    confirm_entry.connect_changed(move |_| update_response());
+
+    loop {
+        if dialog.choose_future(appwindow).await.as_str() != "set" {
+            return;
+        }
+        if todo.is_encrypted() && !todo.passphrase_matches(&current_entry.text()) {
+            current_entry.add_css_class("error");
+            continue;
+        }
+        break;
+    }
+
+    if let Err(e) = todo.set_passphrase(Some(&new_entry.text())) {
+        error!("Setting passphrase failed, Err: {e:?}");
+    }
+}
+
+/// Stores the data file unencrypted again after confirming the current passphrase.
+pub(crate) async fn dialog_remove_passphrase(appwindow: &RnAppWindow) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_remove_passphrase").unwrap();
+    let entry: adw::PasswordEntryRow =
+        builder.object("remove_passphrase_entry").unwrap();
+    let todo = appwindow.todo();
+
+    loop {
+        if dialog.choose_future(appwindow).await.as_str() != "remove" {
+            return;
+        }
+        if !todo.passphrase_matches(&entry.text()) {
+            entry.add_css_class("error");
+            continue;
+        }
+        break;
+    }
+
+    if let Err(e) = todo.set_passphrase(None) {
+        error!("Removing passphrase failed, Err: {e:?}");
+    }
+}
 #[allow(unused)]
 const WORKSPACELISTENTRY_ICONS_LIST: &[&str] = &[
     "workspacelistentryicon-bandaid-symbolic",
@@ -1,8 +1,19 @@
 // Imports
 use crate::config;
-use std::ffi::OsStr;
+use once_cell::sync::OnceCell;
+use std::ffi::{OsStr, OsString};
 use std::path::{Component, Path, PathBuf};
 
+/// The command line flag that enables the portable mode.
+pub(crate) const PORTABLE_FLAG: &str = "portable";
+pub(crate) const PORTABLE_FLAG_SHORT: u8 = b'p';
+/// A file with this name next to the executable enables the portable mode.
+const PORTABLE_MARKER: &str = "portable";
+/// The directory next to the executable that holds data, backups and settings in portable mode.
+const PORTABLE_DATA_DIR: &str = "mytool-data";
+
+static PORTABLE_DIR: OnceCell<Option<PathBuf>> = OnceCell::new();
+
 pub(crate) fn lib_dir() -> anyhow::Result<PathBuf> {
     if cfg!(target_os = "windows") {
         let exec_dir = exec_parent_dir()?;
@@ -92,6 +103,51 @@ pub(crate) fn setup_env() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Enables the portable mode when the marker file exists next to the executable or the flag was passed.
+///
+/// Must be called before the app and its settings are created.
+pub(crate) fn setup_portable(
+    args: impl Iterator<Item = OsString>,
+) -> anyhow::Result<()> {
+    let exec_dir = exec_parent_dir()?;
+    let enabled = portable_flag_given(args) || exec_dir.join(PORTABLE_MARKER).exists();
+
+    let portable_dir = if enabled {
+        let dir = exec_dir.join(PORTABLE_DATA_DIR);
+        std::fs::create_dir_all(&dir)?;
+        Some(dir)
+    } else {
+        None
+    };
+    PORTABLE_DIR
+        .set(portable_dir)
+        .map_err(|_| anyhow::anyhow!("portable mode is already set up"))
+}
+
+/// Whether the portable flag is among the options, read the way GApplication parses them.
+///
+/// Short options may be grouped (`-qp`) and everything after `--` is a file. None of the
+/// app's options take a value, so there are no values to skip.
+fn portable_flag_given(mut args: impl Iterator<Item = OsString>) -> bool {
+    let flag = format!("--{PORTABLE_FLAG}");
+    // The program name
+    args.next();
+    args.take_while(|arg| arg != OsStr::new("--"))
+        .any(|arg| match arg.to_str() {
+            Some(arg) if arg.starts_with("--") => arg == flag,
+            Some(arg) => arg.strip_prefix('-').is_some_and(|shorts| {
+                shorts.bytes().any(|short| short == PORTABLE_FLAG_SHORT)
+            }),
+            // Options are plain ASCII, so this can only be a file
+            None => false,
+        })
+}
+
+/// The directory for data, backups and settings when running in portable mode.
+pub(crate) fn portable_dir() -> Option<PathBuf> {
+    PORTABLE_DIR.get().cloned().flatten()
+}
+
 fn exec_parent_dir() -> anyhow::Result<PathBuf> {
     Ok(std::env::current_exe()?
         .parent()
@@ -1,7 +1,9 @@
 // Imports
-use crate::FilterType;
+use crate::views::{filter_count, Query, SmartFilter};
+use crate::{FilterType, RnTodo};
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::{gio::Icon, glib, CompositeTemplate, Image, Label, Widget};
+use gtk::{glib, CompositeTemplate, Image, Label, Revealer, Widget};
+use std::cell::{Cell, RefCell};
 
 mod imp {
     use super::*;
@@ -9,14 +11,17 @@ mod imp {
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/linruohan/mytool/ui/filter_pane_row.ui")]
     pub(crate) struct FilterPaneRow {
-        pub(crate) filter_type: Option<FilterType>,
+        pub(crate) filter_type: Cell<Option<FilterType>>,
+        pub(crate) smart_filter: RefCell<Option<SmartFilter>>,
         // 标题栏的设置按钮
         #[template_child]
-        title_image: TemplateChild<Image>,
+        pub(crate) title_image: TemplateChild<Image>,
         #[template_child]
-        count_label: TemplateChild<Label>,
+        pub(crate) count_revealer: TemplateChild<Revealer>,
         #[template_child]
-        title_label: TemplateChild<Label>,
+        pub(crate) count_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) title_label: TemplateChild<Label>,
     }
 
     #[glib::object_subclass]
@@ -57,16 +62,53 @@ glib::wrapper! {
 
 impl FilterPaneRow {
     pub(crate) fn new(filter: FilterType) -> Self {
-        // glib::Object::new()
-        let filter_type = filter;
-        Self { filter_type }
+        let row: Self = glib::Object::new();
+        row.imp().filter_type.set(Some(filter));
+        row
+    }
+    /// A tile for a saved smart filter.
+    pub(crate) fn new_smart(smart_filter: SmartFilter) -> Self {
+        let row: Self = glib::Object::new();
+        row.imp().smart_filter.replace(Some(smart_filter));
+        row
     }
-    pub(crate) fn filter_type(&self) -> FilterType {
+    /// The built-in filter of the tile, `None` for smart filters.
+    pub(crate) fn filter_type(&self) -> Option<FilterType> {
         self.imp().filter_type.get()
     }
+    pub(crate) fn smart_filter(&self) -> Option<SmartFilter> {
+        self.imp().smart_filter.borrow().clone()
+    }
     pub(crate) fn init(&self) {
-        let icon = Icon::for_string(&self.filter_type().get_icon())
-            .expect("should be a valid Icon");
-        // self.imp().title_image.get().set_gicon(&icon);
+        let imp = self.imp();
+        let Some(filter_type) = self.filter_type() else {
+            if let Some(smart_filter) = self.smart_filter() {
+                imp.title_label.set_label(&smart_filter.name);
+                imp.title_image.set_icon_name(Some("edit-find-symbolic"));
+                self.add_css_class("filter_smart");
+            }
+            return;
+        };
+        imp.title_label.set_label(&filter_type.to_string());
+        imp.title_image.set_icon_name(Some(&filter_type.get_icon()));
+
+        self.add_css_class(filter_type.css_class());
+    }
+    /// Counts the tasks the filter shows.
+    pub(crate) fn update_count(&self, todo: &RnTodo) {
+        let count = match (self.filter_type(), self.smart_filter()) {
+            (Some(filter_type), _) => filter_count(todo, filter_type),
+            (None, Some(smart_filter)) => {
+                Query::parse(&smart_filter.query).map_or(0, |query| query.count(todo))
+            }
+            (None, None) => 0,
+        };
+        self.set_count(count);
+    }
+    /// Shows the count, hidden when it is zero.
+    pub(crate) fn set_count(&self, count: usize) {
+        let imp = self.imp();
+        imp.count_label.set_label(&count.to_string());
+        imp.count_revealer.set_reveal_child(count > 0);
     }
 }
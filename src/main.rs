@@ -11,6 +11,7 @@ pub(crate) mod appmenu;
 pub(crate) mod appwindow;
 mod collection_object;
 pub(crate) mod config;
+mod crypto;
 pub(crate) mod dialogs;
 pub(crate) mod env;
 pub(crate) mod globals;
@@ -18,8 +19,13 @@ pub(crate) mod layouts;
 pub(crate) mod mainheader;
 pub(crate) mod sidebar;
 mod task_object;
+mod template;
 pub(crate) mod todo;
+pub(crate) mod views;
 pub(crate) mod myenum;
+mod quick_add;
+pub(crate) mod quick_capture;
+mod search_index;
 mod utils;
 
 pub(crate) use app::RnApp;
@@ -27,8 +33,12 @@ pub(crate) use appmenu::RnAppMenu;
 pub(crate) use appwindow::RnAppWindow;
 pub(crate) use layouts::FilterPaneRow;
 pub(crate) use mainheader::RnMainHeader;
+pub(crate) use quick_capture::RnQuickCapture;
 pub(crate) use sidebar::RnSidebar;
-pub(crate) use todo::RnTodo;
+pub(crate) use todo::{RnTaskBoard, RnTaskDetail, RnTodo};
+pub(crate) use views::{
+    RnCalendarView, RnCompletedView, RnFilterView, RnMatrixView, RnScheduledView, RnSearchView, RnTodayView,
+};
 pub(crate) use myenum::FilterType;
 // Renames
 // Imports
@@ -43,6 +53,9 @@ fn main() -> glib::ExitCode {
     if let Err(e) = env::setup_env() {
         eprintln!("failed to setup env, Err: {e:?}");
     }
+    if let Err(e) = env::setup_portable(std::env::args_os()) {
+        eprintln!("failed to setup portable mode, Err: {e:?}");
+    }
     if let Err(e) = setup_i18n() {
         eprintln!("failed to setup i18n, Err: {e:?}");
     }
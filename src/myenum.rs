@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FilterType {
     INBOX,
     TODAY,
@@ -30,14 +30,27 @@ impl FilterType {
             FilterType::COMPLETED => "check-round-outline-symbolic".to_string(),
         }
     }
-    pub(crate) fn get_color(&self) -> String {
+    /// The CSS class of the tile, it tints the icon with the color of the filter.
+    pub(crate) fn css_class(&self) -> &'static str {
         match self {
-            FilterType::INBOX => "#3584e4".to_string(),
-            FilterType::TODAY => "#33d17a".to_string(),
-            FilterType::SCHEDULED => "#9141ac".to_string(),
-            FilterType::PINBOARD => "#ed333b".to_string(),
-            FilterType::LABELS => "#986a44".to_string(),
-            FilterType::COMPLETED => "#ff7800".to_string(),
+            FilterType::INBOX => "filter_inbox",
+            FilterType::TODAY => "filter_today",
+            FilterType::SCHEDULED => "filter_scheduled",
+            FilterType::PINBOARD => "filter_pinboard",
+            FilterType::LABELS => "filter_labels",
+            FilterType::COMPLETED => "filter_completed",
+        }
+    }
+
+    /// The page of the views stack that shows the filter, if there is one.
+    pub(crate) fn page_name(&self) -> Option<&'static str> {
+        match self {
+            FilterType::INBOX => Some("done_page"),
+            FilterType::TODAY => Some("today_page"),
+            FilterType::SCHEDULED => Some("scheduled_page"),
+            FilterType::PINBOARD => None,
+            FilterType::LABELS => None,
+            FilterType::COMPLETED => Some("completed_page"),
         }
     }
 }
@@ -0,0 +1,843 @@
+mod zh;
+
+// Imports
+use crate::task_object::{
+    RecurrenceUnit, TaskDue, TaskObject, TaskRecurrence, MAX_PRIORITY,
+};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, NaiveTime, Weekday};
+use gtk::{gio, glib, prelude::*};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ops::Range;
+
+static TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(\d{1,2})(?::(\d{2}))?(am|pm)|(\d{1,2}):(\d{2})|noon|midnight)$")
+        .expect("the time pattern is valid")
+});
+static ISO_DATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4})-(\d{1,2})-(\d{1,2})$").expect("the date pattern is valid")
+});
+static DAY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{1,2})(?:st|nd|rd|th)?$").expect("the day pattern is valid")
+});
+static PRIORITY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(r"^p([1-{MAX_PRIORITY}])$"))
+        .expect("the priority pattern is valid")
+});
+
+/// The language that dates, times and recurrences are written in.
+///
+/// English phrases are understood in every language.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum DateLanguage {
+    #[default]
+    English,
+    /// Phrases like `明天下午三点`, `下周一`, `三天后`, `月底` and `每周五`
+    Chinese,
+}
+
+impl DateLanguage {
+    /// The language of the `quick-add-language` setting, `auto` follows the UI language.
+    ///
+    /// Without the settings schema it is `auto` as well.
+    pub(crate) fn from_settings(app_settings: Option<&gio::Settings>) -> Self {
+        let setting = app_settings
+            .map(|app_settings| app_settings.string("quick-add-language"))
+            .unwrap_or_default();
+        Self::from_setting(&setting)
+    }
+
+    fn from_setting(setting: &str) -> Self {
+        match setting {
+            "en" => Self::English,
+            "zh" => Self::Chinese,
+            _ => Self::from_ui_language(),
+        }
+    }
+
+    fn from_ui_language() -> Self {
+        let chinese = glib::language_names()
+            .first()
+            .is_some_and(|name| name.starts_with("zh"));
+        if chinese {
+            Self::Chinese
+        } else {
+            Self::English
+        }
+    }
+}
+
+/// What a recognized part of the quick add text sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Date,
+    Time,
+    Recurrence,
+    Collection,
+    Label,
+    Priority,
+}
+
+/// A recognized part of the quick add text, `range` are the bytes it spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Token {
+    pub(crate) kind: TokenKind,
+    pub(crate) range: Range<usize>,
+    /// How the parsed value is shown to the user
+    pub(crate) display: String,
+}
+
+/// A task as described by the text of the quick add entry.
+///
+/// Understands phrases like `Call vendor tomorrow 3pm #Ops @urgent p1 every monday`:
+/// - dates: `today`, `tomorrow`, weekdays, `next friday`, `in 3 days`, `oct 20`,
+///   `20 oct` and `2024-10-20`, optionally with a time like `3pm`, `at 15:30` or `noon`
+/// - `every day`, `every 2 weeks`, `every monday`, `daily`, `weekly`, `monthly`
+///   and `yearly` for recurrence
+/// - `#Collection` for the target collection, if one has that title
+/// - `@label` for labels, stored as `#label` in the content
+/// - `p1` to `p3` for the priority
+///
+/// Text in double quotes and words that start with `\` are kept verbatim. See
+/// [DateLanguage] for dates in other languages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct QuickAdd {
+    pub(crate) content: String,
+    pub(crate) due: Option<TaskDue>,
+    /// The title of the target collection, as it was found in `collection_titles`
+    pub(crate) collection: Option<String>,
+    pub(crate) labels: Vec<String>,
+    pub(crate) priority: Option<u8>,
+    pub(crate) recurrence: Option<TaskRecurrence>,
+    pub(crate) tokens: Vec<Token>,
+}
+
+/// A word of the text, with the bytes it spans.
+#[derive(Debug, Clone)]
+struct Word<'a> {
+    text: &'a str,
+    range: Range<usize>,
+    /// Quoted and escaped words are never parsed
+    literal: bool,
+}
+
+fn split_words(text: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = text[start..].find(|c: char| !c.is_whitespace()) {
+        start += offset;
+        if let Some(rest) = text[start..].strip_prefix('"') {
+            // An unclosed quote runs until the end of the text
+            let (quoted, end) = match rest.find('"') {
+                Some(len) => (&rest[..len], start + len + 2),
+                None => (rest, text.len()),
+            };
+            words.push(Word {
+                text: quoted,
+                range: start..end,
+                literal: true,
+            });
+            start = end;
+        } else {
+            let end = text[start..]
+                .find(char::is_whitespace)
+                .map_or(text.len(), |len| start + len);
+            let word = &text[start..end];
+            words.push(match word.strip_prefix('\\') {
+                Some(escaped) => Word {
+                    text: escaped,
+                    range: start..end,
+                    literal: true,
+                },
+                None => Word {
+                    text: word,
+                    range: start..end,
+                    literal: false,
+                },
+            });
+            start = end;
+        }
+    }
+    words
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        // `sat` and `sun` are left out, they are too common as words
+        "saturday" => Weekday::Sat,
+        "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov",
+        "dec",
+    ];
+    const FULL_MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let word = word.trim_end_matches('.');
+    MONTHS
+        .iter()
+        .position(|month| *month == word)
+        .or_else(|| FULL_MONTHS.iter().position(|month| *month == word))
+        .or_else(|| (word == "sept").then_some(8))
+        .map(|i| i as u32 + 1)
+}
+
+fn parse_unit(word: &str) -> Option<RecurrenceUnit> {
+    match word.trim_end_matches('s') {
+        "day" => Some(RecurrenceUnit::Day),
+        "week" => Some(RecurrenceUnit::Week),
+        "month" => Some(RecurrenceUnit::Month),
+        "year" => Some(RecurrenceUnit::Year),
+        _ => None,
+    }
+}
+
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    let captures = TIME_RE.captures(word)?;
+    match captures.get(0)?.as_str() {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+    if let Some(hour) = captures.get(1) {
+        let hour: u32 = hour.as_str().parse().ok()?;
+        let minute: u32 = captures
+            .get(2)
+            .map_or(Some(0), |m| m.as_str().parse().ok())?;
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        let hour = match (&captures[3], hour) {
+            ("am", 12) => 0,
+            ("am", hour) => hour,
+            ("pm", 12) => 12,
+            (_, hour) => hour + 12,
+        };
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    } else {
+        NaiveTime::from_hms_opt(captures[4].parse().ok()?, captures[5].parse().ok()?, 0)
+    }
+}
+
+/// The date that lies the amount of units after `from`, `None` when it is out of range.
+fn after(from: NaiveDate, amount: u32, unit: RecurrenceUnit) -> Option<NaiveDate> {
+    match unit {
+        RecurrenceUnit::Day => from.checked_add_days(Days::new(u64::from(amount))),
+        RecurrenceUnit::Week => from.checked_add_days(Days::new(7 * u64::from(amount))),
+        RecurrenceUnit::Month => from.checked_add_months(Months::new(amount)),
+        RecurrenceUnit::Year => {
+            from.checked_add_months(Months::new(amount.checked_mul(12)?))
+        }
+    }
+}
+
+/// The first day on or after `from` that is the weekday.
+fn upcoming(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days = (7 + weekday.num_days_from_monday()
+        - from.weekday().num_days_from_monday())
+        % 7;
+    from + Days::new(u64::from(days))
+}
+
+/// The month and day in the current year, or in the next one when it has passed.
+fn month_day(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+fn format_recurrence(recurrence: TaskRecurrence, weekday: Option<Weekday>) -> String {
+    match weekday {
+        Some(weekday) => format!("every {weekday}"),
+        None => recurrence.description(),
+    }
+}
+
+fn format_date(date: NaiveDate, today: NaiveDate) -> String {
+    if date == today {
+        String::from("Today")
+    } else if date == today + Days::new(1) {
+        String::from("Tomorrow")
+    } else if date.year() == today.year() {
+        date.format("%a, %b %-d").to_string()
+    } else {
+        date.format("%a, %b %-d, %Y").to_string()
+    }
+}
+
+/// The byte ranges of the words of the text, only split at whitespace.
+fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(word_start), true) => {
+                ranges.push(word_start..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        ranges.push(word_start..text.len());
+    }
+    ranges
+}
+
+/// Escapes the words at `starts` with `\`, so that they are kept verbatim.
+fn escape_words(text: &mut String, starts: &[usize]) {
+    // Later words first, so that the earlier starts stay in place
+    for &start in starts.iter().rev() {
+        text.insert(start, '\\');
+    }
+}
+
+/// The content with every word escaped that quick add would read as something else,
+/// so that parsing it again gives the same content.
+fn escape_content(content: &str, today: NaiveDate) -> String {
+    let mut text = content.to_string();
+    let starts: Vec<usize> = word_ranges(&text)
+        .into_iter()
+        .map(|range| range.start)
+        .filter(|&start| text[start..].starts_with(['#', '@', '\\', '"']))
+        .collect();
+    escape_words(&mut text, &starts);
+
+    // Escaping a phrase can let a later one be read, so repeat until nothing is read
+    loop {
+        let tokens = QuickAdd::parse(&text, today, &[], DateLanguage::Chinese).tokens;
+        if tokens.is_empty() {
+            return text;
+        }
+        let starts: Vec<usize> = word_ranges(&text)
+            .into_iter()
+            .filter(|range| {
+                tokens.iter().any(|token| {
+                    token.range.start < range.end && range.start < token.range.end
+                })
+            })
+            .map(|range| range.start)
+            .collect();
+        if starts.is_empty() {
+            return text;
+        }
+        escape_words(&mut text, &starts);
+    }
+}
+
+/// The task as quick add text, so that pasting it creates the same task.
+pub(crate) fn task_text(task_object: &TaskObject) -> String {
+    let mut text = escape_content(&task_object.content(), Local::now().date_naive());
+    if let Some(due) = task_object.due() {
+        text.push_str(&format!(" {}", due.date.format("%Y-%m-%d")));
+        if let Some(time) = due.time {
+            text.push_str(&format!(" {}", time.format("%H:%M")));
+        }
+    }
+    // Older data might have priorities that can't be written
+    let priority = task_object.priority().min(MAX_PRIORITY);
+    if priority > 0 {
+        text.push_str(&format!(" p{priority}"));
+    }
+    if let Some(recurrence) = task_object.recurrence() {
+        text.push_str(&format!(" {}", recurrence.description()));
+    }
+    text
+}
+
+impl QuickAdd {
+    /// Parses the text, `#Name` only sets the collection if `collection_titles` has it.
+    pub(crate) fn parse(
+        text: &str,
+        today: NaiveDate,
+        collection_titles: &[String],
+        language: DateLanguage,
+    ) -> Self {
+        let mut quick_add = Self::default();
+        let mut date: Option<NaiveDate> = None;
+        let mut time: Option<NaiveTime> = None;
+        // A time like `晚上十二点` alone is due the next day
+        let mut midnight = false;
+        // The weekday of an `every monday` recurrence, for the first due date
+        let mut recurrence_weekday: Option<Weekday> = None;
+
+        // Phrases of other languages are found first and blanked out, they aren't
+        // separated by spaces
+        let blanked;
+        let words = match language {
+            DateLanguage::English => split_words(text),
+            DateLanguage::Chinese => {
+                let literal: Vec<Range<usize>> = split_words(text)
+                    .into_iter()
+                    .filter(|word| word.literal)
+                    .map(|word| word.range)
+                    .collect();
+                let phrases = zh::Phrases::find(text, today, &literal);
+                blanked = phrases.blank(text);
+                if let Some((range, parsed)) = phrases.date {
+                    date = Some(parsed);
+                    quick_add.push_token(
+                        TokenKind::Date,
+                        range,
+                        format_date(parsed, today),
+                    );
+                }
+                midnight = phrases.midnight;
+                if let Some((range, parsed)) = phrases.time {
+                    time = Some(parsed);
+                    let display = parsed.format("%H:%M").to_string();
+                    quick_add.push_token(TokenKind::Time, range, display);
+                }
+                if let Some((range, recurrence, weekday)) = phrases.recurrence {
+                    quick_add.recurrence = Some(recurrence);
+                    recurrence_weekday = weekday;
+                    let display = format_recurrence(recurrence, weekday);
+                    quick_add.push_token(TokenKind::Recurrence, range, display);
+                }
+                split_words(&blanked)
+            }
+        };
+        let lower: Vec<String> =
+            words.iter().map(|word| word.text.to_lowercase()).collect();
+        let parsable = |i: usize| words.get(i).is_some_and(|word| !word.literal);
+        let lower_at = |i: usize| lower.get(i).map(String::as_str).unwrap_or_default();
+        let mut content_words: Vec<(String, Range<usize>)> = Vec::new();
+
+        let mut i = 0;
+        while i < words.len() {
+            let word = &words[i];
+            if word.literal {
+                if !word.text.is_empty() {
+                    content_words.push((word.text.to_string(), word.range.clone()));
+                }
+                i += 1;
+                continue;
+            }
+            let current = lower_at(i);
+            // The number of words the token spans, and what it is
+            let mut matched: Option<(usize, TokenKind, String)> = None;
+
+            if date.is_none() {
+                if let Some((len, parsed)) =
+                    Self::match_date(&lower, &parsable, i, today)
+                {
+                    date = Some(parsed);
+                    matched = Some((len, TokenKind::Date, format_date(parsed, today)));
+                }
+            }
+            if matched.is_none() && time.is_none() {
+                let (len, time_word) = if current == "at" && parsable(i + 1) {
+                    (2, lower_at(i + 1))
+                } else {
+                    (1, current)
+                };
+                if let Some(parsed) = parse_time(time_word) {
+                    time = Some(parsed);
+                    matched = Some((
+                        len,
+                        TokenKind::Time,
+                        parsed.format("%H:%M").to_string(),
+                    ));
+                }
+            }
+            if matched.is_none() && quick_add.recurrence.is_none() {
+                if let Some((len, recurrence, weekday)) =
+                    Self::match_recurrence(&lower, &parsable, i)
+                {
+                    quick_add.recurrence = Some(recurrence);
+                    recurrence_weekday = weekday;
+                    let display = format_recurrence(recurrence, weekday);
+                    matched = Some((len, TokenKind::Recurrence, display));
+                }
+            }
+            if matched.is_none() {
+                if let Some(name) =
+                    word.text.strip_prefix('#').filter(|name| !name.is_empty())
+                {
+                    let key = name.to_lowercase();
+                    if let Some(title) = collection_titles.iter().find(|title| {
+                        title.to_lowercase().split_whitespace().collect::<String>()
+                            == key
+                    }) {
+                        quick_add.collection = Some(title.clone());
+                        matched = Some((1, TokenKind::Collection, title.clone()));
+                    }
+                } else if let Some(label) = word
+                    .text
+                    .strip_prefix('@')
+                    .filter(|label| !label.is_empty())
+                {
+                    let label = label.to_lowercase();
+                    if !quick_add.labels.contains(&label) {
+                        quick_add.labels.push(label.clone());
+                    }
+                    matched = Some((1, TokenKind::Label, format!("#{label}")));
+                } else if let Some(captures) = PRIORITY_RE.captures(current) {
+                    let priority = captures[1].parse().unwrap_or_default();
+                    quick_add.priority = Some(priority);
+                    matched =
+                        Some((1, TokenKind::Priority, format!("Priority {priority}")));
+                }
+            }
+
+            match matched {
+                Some((len, kind, display)) => {
+                    let end = words[i + len - 1].range.end;
+                    quick_add.push_token(kind, word.range.start..end, display);
+                    i += len;
+                }
+                None => {
+                    content_words.push((word.text.to_string(), word.range.clone()));
+                    i += 1;
+                }
+            }
+        }
+
+        // A recurrence or a time alone still needs a day to start on
+        let date =
+            date.or_else(|| match (recurrence_weekday, time, quick_add.recurrence) {
+                (Some(weekday), _, _) => Some(upcoming(today, weekday)),
+                (None, Some(_), _) if midnight => today.succ_opt(),
+                (None, Some(_), _) | (None, None, Some(_)) => Some(today),
+                (None, None, None) => None,
+            });
+        quick_add.due = date.map(|date| TaskDue::new(date, time));
+
+        // Words stay joined if only a phrase was between them, like in `和明天的客户`
+        let mut previous_end: Option<usize> = None;
+        for (word, range) in content_words {
+            if previous_end
+                .is_some_and(|end| text[end..range.start].contains(char::is_whitespace))
+            {
+                quick_add.content.push(' ');
+            }
+            quick_add.content.push_str(&word);
+            previous_end = Some(range.end);
+        }
+        for label in &quick_add.labels {
+            if !quick_add.content.is_empty() {
+                quick_add.content.push(' ');
+            }
+            quick_add.content.push_str(&format!("#{label}"));
+        }
+        quick_add.tokens.sort_by_key(|token| token.range.start);
+        quick_add
+    }
+
+    /// A new open task with the parsed content, due date, priority and recurrence.
+    pub(crate) fn to_task(&self) -> TaskObject {
+        let task = TaskObject::new(false, self.content.clone());
+        task.set_due(self.due);
+        task.set_priority(self.priority.unwrap_or_default());
+        task.set_recurrence(self.recurrence);
+        task
+    }
+
+    fn push_token(&mut self, kind: TokenKind, range: Range<usize>, display: String) {
+        self.tokens.push(Token {
+            kind,
+            range,
+            display,
+        });
+    }
+
+    /// Matches a date that starts at the word `i`, returning how many words it spans.
+    fn match_date(
+        lower: &[String],
+        parsable: &impl Fn(usize) -> bool,
+        i: usize,
+        today: NaiveDate,
+    ) -> Option<(usize, NaiveDate)> {
+        let word = |i: usize| lower.get(i).filter(|_| parsable(i)).map(String::as_str);
+        let current = word(i)?;
+
+        match current {
+            "today" => return Some((1, today)),
+            "tomorrow" | "tmr" | "tmrw" => return Some((1, today + Days::new(1))),
+            _ => {}
+        }
+        if let Some(weekday) = parse_weekday(current) {
+            return Some((1, upcoming(today, weekday)));
+        }
+        if current == "next" {
+            if let Some(weekday) = word(i + 1).and_then(parse_weekday) {
+                return Some((2, upcoming(today + Days::new(1), weekday)));
+            }
+            if word(i + 1) == Some("week") {
+                return Some((2, upcoming(today + Days::new(1), Weekday::Mon)));
+            }
+        }
+        if current == "in" {
+            let amount: u32 = word(i + 1)?.parse().ok()?;
+            let unit = parse_unit(word(i + 2)?)?;
+            return Some((3, after(today, amount, unit)?));
+        }
+        if let Some(captures) = ISO_DATE_RE.captures(current) {
+            let date = NaiveDate::from_ymd_opt(
+                captures[1].parse().ok()?,
+                captures[2].parse().ok()?,
+                captures[3].parse().ok()?,
+            )?;
+            return Some((1, date));
+        }
+        // `oct 20` and `20 oct`
+        if let Some(month) = parse_month(current) {
+            let day = DAY_RE.captures(word(i + 1)?)?[1].parse().ok()?;
+            return Some((2, month_day(today, month, day)?));
+        }
+        if let Some(captures) = DAY_RE.captures(current) {
+            let month = parse_month(word(i + 1)?)?;
+            return Some((2, month_day(today, month, captures[1].parse().ok()?)?));
+        }
+        None
+    }
+
+    /// Matches a recurrence that starts at the word `i`, returning how many words it spans.
+    fn match_recurrence(
+        lower: &[String],
+        parsable: &impl Fn(usize) -> bool,
+        i: usize,
+    ) -> Option<(usize, TaskRecurrence, Option<Weekday>)> {
+        let word = |i: usize| lower.get(i).filter(|_| parsable(i)).map(String::as_str);
+
+        let unit = match word(i)? {
+            "daily" => Some(RecurrenceUnit::Day),
+            "weekly" => Some(RecurrenceUnit::Week),
+            "monthly" => Some(RecurrenceUnit::Month),
+            "yearly" | "annually" => Some(RecurrenceUnit::Year),
+            "every" => None,
+            _ => return None,
+        };
+        if let Some(unit) = unit {
+            return Some((1, TaskRecurrence::new(1, unit), None));
+        }
+
+        let next = word(i + 1)?;
+        if let Some(weekday) = parse_weekday(next) {
+            let recurrence = TaskRecurrence::new(1, RecurrenceUnit::Week);
+            return Some((2, recurrence, Some(weekday)));
+        }
+        if let Some(unit) = parse_unit(next) {
+            return Some((2, TaskRecurrence::new(1, unit), None));
+        }
+        let interval: u32 = next.parse().ok()?;
+        let unit = parse_unit(word(i + 2)?)?;
+        Some((3, TaskRecurrence::new(interval, unit), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, 16).unwrap()
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    fn parse(text: &str) -> QuickAdd {
+        QuickAdd::parse(text, today(), &[String::from("Ops")], DateLanguage::English)
+    }
+
+    fn due(text: &str) -> Option<TaskDue> {
+        parse(text).due
+    }
+
+    #[test]
+    fn parse_everything() {
+        let quick_add = parse("Call vendor tomorrow 3pm #Ops @Urgent p1 every monday");
+        assert_eq!(quick_add.content, "Call vendor #urgent");
+        assert_eq!(quick_add.due, Some(TaskDue::new(date(10, 17), time(15, 0))));
+        assert_eq!(quick_add.collection.as_deref(), Some("Ops"));
+        assert_eq!(quick_add.labels, ["urgent"]);
+        assert_eq!(quick_add.priority, Some(1));
+        assert_eq!(
+            quick_add.recurrence,
+            Some(TaskRecurrence::new(1, RecurrenceUnit::Week))
+        );
+        let kinds: Vec<TokenKind> =
+            quick_add.tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::Date,
+                TokenKind::Time,
+                TokenKind::Collection,
+                TokenKind::Label,
+                TokenKind::Priority,
+                TokenKind::Recurrence,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_dates() {
+        for (text, expected) in [
+            ("today", today()),
+            ("Tomorrow", date(10, 17)),
+            ("friday", date(10, 18)),
+            ("wed", today()),
+            ("next monday", date(10, 21)),
+            ("next wednesday", date(10, 23)),
+            ("next week", date(10, 21)),
+            ("in 3 days", date(10, 19)),
+            ("in 2 weeks", date(10, 30)),
+            ("in 4 months", NaiveDate::from_ymd_opt(2025, 2, 16).unwrap()),
+            ("in 1 year", NaiveDate::from_ymd_opt(2025, 10, 16).unwrap()),
+            ("oct 20", date(10, 20)),
+            ("20th October", date(10, 20)),
+            ("jan 5", NaiveDate::from_ymd_opt(2025, 1, 5).unwrap()),
+            ("2024-12-01", date(12, 1)),
+        ] {
+            assert_eq!(due(text), Some(TaskDue::new(expected, None)), "{text}");
+        }
+    }
+
+    #[test]
+    fn parse_times() {
+        for (text, expected) in [
+            ("at 15:30", time(15, 30)),
+            ("9am", time(9, 0)),
+            ("12am", time(0, 0)),
+            ("12:45pm", time(12, 45)),
+            ("noon", time(12, 0)),
+            ("midnight", time(0, 0)),
+        ] {
+            // A time alone is due today
+            assert_eq!(due(text), Some(TaskDue::new(today(), expected)), "{text}");
+        }
+        let quick_add = parse("Take 13pm");
+        assert_eq!(quick_add.content, "Take 13pm");
+        assert_eq!(quick_add.due, None);
+    }
+
+    #[test]
+    fn parse_recurrences() {
+        for (text, interval, unit) in [
+            ("daily", 1, RecurrenceUnit::Day),
+            ("every week", 1, RecurrenceUnit::Week),
+            ("every 2 weeks", 2, RecurrenceUnit::Week),
+            ("monthly", 1, RecurrenceUnit::Month),
+            ("every 3 years", 3, RecurrenceUnit::Year),
+        ] {
+            let quick_add = parse(text);
+            assert_eq!(
+                quick_add.recurrence,
+                Some(TaskRecurrence::new(interval, unit)),
+                "{text}"
+            );
+            // A recurrence alone starts today
+            assert_eq!(quick_add.due, Some(TaskDue::new(today(), None)), "{text}");
+        }
+        // `every friday` starts on the upcoming friday
+        assert_eq!(
+            due("Gym every friday"),
+            Some(TaskDue::new(date(10, 18), None))
+        );
+    }
+
+    #[test]
+    fn parse_literals() {
+        let quick_add = parse(r#"Read "tomorrow never dies" \p1 #Unknown"#);
+        assert_eq!(quick_add.content, "Read tomorrow never dies p1 #Unknown");
+        assert_eq!(quick_add.due, None);
+        assert_eq!(quick_add.priority, None);
+        assert_eq!(quick_add.collection, None);
+    }
+
+    #[test]
+    fn overflowing_dates_are_no_dates() {
+        for text in [
+            "in 4294967295 years",
+            "in 4294967295 days",
+            "in 99999999999 days",
+        ] {
+            let quick_add = parse(text);
+            assert_eq!(quick_add.due, None, "{text}");
+            assert_eq!(quick_add.content, text);
+        }
+        let max = NaiveDate::MAX;
+        assert_eq!(after(max, 1, RecurrenceUnit::Day), None);
+        assert_eq!(after(today(), u32::MAX, RecurrenceUnit::Week), None);
+        assert_eq!(after(today(), u32::MAX, RecurrenceUnit::Month), None);
+        assert_eq!(after(today(), u32::MAX, RecurrenceUnit::Year), None);
+        // The day is clamped to the end of shorter months
+        assert_eq!(
+            after(date(1, 31), 1, RecurrenceUnit::Month),
+            Some(date(2, 29))
+        );
+    }
+
+    #[test]
+    fn copied_tasks_paste_the_same() {
+        let content = r#"Call #ops about p1 "friday" 明天 at \\server in 3 days #ops"#;
+        let task_object = TaskObject::new(false, content.to_string());
+        task_object.set_due(Some(TaskDue::new(date(10, 20), time(9, 30))));
+        task_object.set_priority(2);
+        task_object.set_recurrence(Some(TaskRecurrence::new(2, RecurrenceUnit::Week)));
+
+        for language in [DateLanguage::English, DateLanguage::Chinese] {
+            let text = task_text(&task_object);
+            let pasted =
+                QuickAdd::parse(&text, today(), &[String::from("Ops")], language);
+            assert_eq!(pasted.content, content, "{text}");
+            assert_eq!(pasted.collection, None, "{text}");
+            assert_eq!(pasted.due, task_object.due(), "{text}");
+            assert_eq!(pasted.priority, Some(2), "{text}");
+            assert_eq!(pasted.recurrence, task_object.recurrence(), "{text}");
+        }
+        // Text that was written by hand still names the collection
+        let pasted = parse("Call vendor #Ops");
+        assert_eq!(pasted.content, "Call vendor");
+        assert_eq!(pasted.collection.as_deref(), Some("Ops"));
+    }
+
+    #[test]
+    fn priorities_the_menu_offers() {
+        for (text, priority) in [("p1", Some(1)), ("P3", Some(3))] {
+            assert_eq!(parse(text).priority, priority, "{text}");
+        }
+        for text in ["p0", "p4", "p9", "p10"] {
+            let quick_add = parse(text);
+            assert_eq!(quick_add.priority, None, "{text}");
+            assert_eq!(quick_add.content, text);
+        }
+        let task_object = TaskObject::new(false, String::from("Old"));
+        task_object.set_priority(7);
+        assert_eq!(task_text(&task_object), "Old p3");
+    }
+}
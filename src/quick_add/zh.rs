@@ -0,0 +1,476 @@
+// Imports
+use super::{after, month_day, upcoming};
+use crate::task_object::{RecurrenceUnit, TaskRecurrence};
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Weekday};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::ops::Range;
+
+/// Digits or Chinese numerals like `三`, `两` and `二十三`.
+const NUMBER: &str = "[0-9]+|[零〇一二两三四五六七八九十]+";
+
+static RECURRENCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        "每(?:(?:周|星期|礼拜)(?P<weekday>[一二三四五六日天])\
+         |(?P<interval>{NUMBER})?个?(?P<unit>天|日|周|星期|礼拜|月|年))"
+    ))
+    .expect("the recurrence pattern is valid")
+});
+static DATE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        "(?P<day>大后天|后天|明天|明日|明晚|今天|今日|今晚)\
+         |(?P<week>下下|下个?|本|这个?)?(?:周|星期|礼拜)(?P<weekday>[一二三四五六日天])\
+         |(?P<amount>{NUMBER})个?(?P<unit>天|周|星期|礼拜|月|年)(?:以后|之后|后)\
+         |(?P<next_month>下个?月?)?月底\
+         |(?P<month>{NUMBER})月(?P<month_day>{NUMBER})[日号]"
+    ))
+    .expect("the date pattern is valid")
+});
+static TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        "(?P<period>凌晨|早上|早晨|上午|中午|下午|傍晚|晚上)?\
+         (?:(?P<hour>{NUMBER})(?:点钟?|时)(?:(?P<half>半)|(?P<minute>{NUMBER})分?)?\
+         |(?P<clock_hour>[0-9]{{1,2}})[:：](?P<clock_minute>[0-9]{{2}}))"
+    ))
+    .expect("the time pattern is valid")
+});
+
+/// The dates, times and recurrences written in Chinese, with the bytes they span.
+///
+/// Chinese isn't separated by spaces, so the phrases are searched for anywhere in the
+/// text instead of word by word.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Phrases {
+    pub(super) date: Option<(Range<usize>, NaiveDate)>,
+    pub(super) time: Option<(Range<usize>, NaiveTime)>,
+    /// The time is the midnight that ends the day, like `晚上十二点`
+    pub(super) midnight: bool,
+    /// The weekday of a `每周五` recurrence, for the first due date
+    pub(super) recurrence: Option<(Range<usize>, TaskRecurrence, Option<Weekday>)>,
+}
+
+impl Phrases {
+    /// Finds the first phrase of each kind that doesn't overlap the literal ranges.
+    pub(super) fn find(text: &str, today: NaiveDate, literal: &[Range<usize>]) -> Self {
+        let mut phrases = Self::default();
+        // Found phrases are blanked, so that `每周五` isn't also found as the date `周五`
+        let mut text = text.to_string();
+
+        phrases.recurrence = find_first(&RECURRENCE_RE, &text, literal, |captures| {
+            parse_recurrence(captures)
+        })
+        .map(|(range, (recurrence, weekday))| (range, recurrence, weekday));
+        if let Some((range, ..)) = &phrases.recurrence {
+            blank(&mut text, range);
+        }
+
+        // `今晚` and `明晚` make the time an evening one
+        let mut evening = false;
+        phrases.date = find_first(&DATE_RE, &text, literal, |captures| {
+            let date = parse_date(captures, today)?;
+            evening = matches!(
+                captures.name("day").map(|m| m.as_str()),
+                Some("今晚" | "明晚")
+            );
+            Some(date)
+        });
+        if let Some((range, _)) = &phrases.date {
+            blank(&mut text, range);
+        }
+
+        let time = find_first(&TIME_RE, &text, literal, |captures| {
+            // The hour of `快一点` is part of a word, it needs a day period before it
+            // or to start the word
+            if captures.name("hour").is_some()
+                && captures.name("period").is_none()
+                && text[..captures.get(0)?.start()]
+                    .chars()
+                    .next_back()
+                    .is_some_and(is_cjk)
+            {
+                return None;
+            }
+            parse_time(captures, evening)
+        });
+        if let Some((range, (time, midnight))) = time {
+            phrases.time = Some((range, time));
+            phrases.midnight = midnight;
+        }
+        if let (true, Some((_, date))) = (phrases.midnight, &mut phrases.date) {
+            *date = date.succ_opt().unwrap_or(*date);
+        }
+        phrases
+    }
+
+    /// The text with the phrases replaced by spaces, the other bytes keep their offsets.
+    pub(super) fn blank(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        let ranges = [
+            self.date.as_ref().map(|(range, _)| range),
+            self.time.as_ref().map(|(range, _)| range),
+            self.recurrence.as_ref().map(|(range, ..)| range),
+        ];
+        for range in ranges.into_iter().flatten() {
+            blank(&mut text, range);
+        }
+        text
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '〇')
+}
+
+fn blank(text: &mut String, range: &Range<usize>) {
+    text.replace_range(range.clone(), &" ".repeat(range.len()));
+}
+
+/// The first match outside of the literal ranges that `parse` accepts.
+fn find_first<T>(
+    regex: &Regex,
+    text: &str,
+    literal: &[Range<usize>],
+    mut parse: impl FnMut(&Captures) -> Option<T>,
+) -> Option<(Range<usize>, T)> {
+    regex.captures_iter(text).find_map(|captures| {
+        let range = captures.get(0)?.range();
+        if literal
+            .iter()
+            .any(|literal| literal.start < range.end && range.start < literal.end)
+        {
+            return None;
+        }
+        Some((range, parse(&captures)?))
+    })
+}
+
+fn parse_digit(c: char) -> Option<u32> {
+    let digit = match c {
+        '零' | '〇' => 0,
+        '一' => 1,
+        '二' | '两' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
+fn parse_single_digit(text: &str) -> Option<u32> {
+    let mut chars = text.chars();
+    let digit = parse_digit(chars.next()?)?;
+    chars.next().is_none().then_some(digit)
+}
+
+/// Parses digits and Chinese numerals up to `九十九`.
+fn parse_number(text: &str) -> Option<u32> {
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        return text.parse().ok();
+    }
+    match text.split_once('十') {
+        // `十`, `十二`, `二十` and `二十三`
+        Some((tens, ones)) => {
+            let tens = if tens.is_empty() {
+                1
+            } else {
+                parse_single_digit(tens)?
+            };
+            let ones = if ones.is_empty() {
+                0
+            } else {
+                parse_single_digit(ones)?
+            };
+            Some(tens * 10 + ones)
+        }
+        None => parse_single_digit(text),
+    }
+}
+
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    let weekday = match text {
+        "一" => Weekday::Mon,
+        "二" => Weekday::Tue,
+        "三" => Weekday::Wed,
+        "四" => Weekday::Thu,
+        "五" => Weekday::Fri,
+        "六" => Weekday::Sat,
+        "日" | "天" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+fn parse_unit(text: &str) -> Option<RecurrenceUnit> {
+    match text {
+        "天" | "日" => Some(RecurrenceUnit::Day),
+        "周" | "星期" | "礼拜" => Some(RecurrenceUnit::Week),
+        "月" => Some(RecurrenceUnit::Month),
+        "年" => Some(RecurrenceUnit::Year),
+        _ => None,
+    }
+}
+
+/// The last day of the month, `months` after the month of `date`.
+fn end_of_month(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.with_day(1)?
+        .checked_add_months(Months::new(months + 1))?
+        .checked_sub_days(Days::new(1))
+}
+
+fn parse_recurrence(captures: &Captures) -> Option<(TaskRecurrence, Option<Weekday>)> {
+    if let Some(weekday) = captures.name("weekday") {
+        let weekday = parse_weekday(weekday.as_str())?;
+        return Some((TaskRecurrence::new(1, RecurrenceUnit::Week), Some(weekday)));
+    }
+    let interval = match captures.name("interval") {
+        Some(interval) => parse_number(interval.as_str()).filter(|i| *i > 0)?,
+        None => 1,
+    };
+    let unit = parse_unit(captures.name("unit")?.as_str())?;
+    Some((TaskRecurrence::new(interval, unit), None))
+}
+
+fn parse_date(captures: &Captures, today: NaiveDate) -> Option<NaiveDate> {
+    if let Some(day) = captures.name("day") {
+        let days = match day.as_str() {
+            "今天" | "今日" | "今晚" => 0,
+            "明天" | "明日" | "明晚" => 1,
+            "后天" => 2,
+            "大后天" => 3,
+            _ => return None,
+        };
+        return today.checked_add_days(Days::new(days));
+    }
+    if let Some(weekday) = captures.name("weekday") {
+        let weekday = parse_weekday(weekday.as_str())?;
+        // `下周一` is the monday of the next week, `周一` the upcoming one
+        let weeks = match captures.name("week").map(|m| m.as_str()) {
+            None => return Some(upcoming(today, weekday)),
+            Some("下下") => 2,
+            Some("下" | "下个") => 1,
+            Some(_) => 0,
+        };
+        let monday = today.checked_sub_days(Days::new(u64::from(
+            today.weekday().num_days_from_monday(),
+        )))?;
+        return monday.checked_add_days(Days::new(
+            7 * weeks + u64::from(weekday.num_days_from_monday()),
+        ));
+    }
+    if let Some(amount) = captures.name("amount") {
+        let amount = parse_number(amount.as_str())?;
+        let unit = parse_unit(captures.name("unit")?.as_str())?;
+        return after(today, amount, unit);
+    }
+    if let Some(month) = captures.name("month") {
+        let month = parse_number(month.as_str())?;
+        let day = parse_number(captures.name("month_day")?.as_str())?;
+        return month_day(today, month, day);
+    }
+    // `月底` and `下个月底`
+    let months = u32::from(captures.name("next_month").is_some());
+    end_of_month(today, months)
+}
+
+/// The time, and whether it is the midnight that ends the day.
+fn parse_time(captures: &Captures, evening: bool) -> Option<(NaiveTime, bool)> {
+    let (hour, minute) = match captures.name("hour") {
+        Some(hour) => {
+            let minute = if captures.name("half").is_some() {
+                30
+            } else {
+                captures
+                    .name("minute")
+                    .map_or(Some(0), |minute| parse_number(minute.as_str()))?
+            };
+            (parse_number(hour.as_str())?, minute)
+        }
+        None => (
+            captures.name("clock_hour")?.as_str().parse().ok()?,
+            captures.name("clock_minute")?.as_str().parse().ok()?,
+        ),
+    };
+    let period = captures.name("period").map(|m| m.as_str());
+    let afternoon = evening || matches!(period, Some("下午" | "傍晚" | "晚上"));
+    // `晚上十二点`
+    let midnight = hour == 12 && (evening || period == Some("晚上"));
+    let hour = match hour {
+        hour if afternoon && hour < 12 => hour + 12,
+        // `中午一点`
+        hour if period == Some("中午") && hour < 6 => hour + 12,
+        _ if midnight => 0,
+        hour => hour,
+    };
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quick_add::{DateLanguage, QuickAdd};
+    use crate::task_object::TaskDue;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, 16).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    fn parse(text: &str) -> QuickAdd {
+        QuickAdd::parse(text, today(), &[], DateLanguage::Chinese)
+    }
+
+    fn due(text: &str) -> Option<TaskDue> {
+        parse(text).due
+    }
+
+    #[test]
+    fn parse_numbers() {
+        for (text, expected) in [
+            ("0", Some(0)),
+            ("12", Some(12)),
+            ("三", Some(3)),
+            ("两", Some(2)),
+            ("十", Some(10)),
+            ("十二", Some(12)),
+            ("二十", Some(20)),
+            ("二十三", Some(23)),
+            ("一百", None),
+            ("十十", None),
+        ] {
+            assert_eq!(parse_number(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn parse_dates() {
+        for (text, expected) in [
+            ("今天", today()),
+            ("明天", date(2024, 10, 17)),
+            ("大后天", date(2024, 10, 19)),
+            ("周五", date(2024, 10, 18)),
+            ("这周一", date(2024, 10, 14)),
+            ("下周一", date(2024, 10, 21)),
+            ("下下星期三", date(2024, 10, 30)),
+            ("三天后", date(2024, 10, 19)),
+            ("两周以后", date(2024, 10, 30)),
+            ("1个月后", date(2024, 11, 16)),
+            ("月底", date(2024, 10, 31)),
+            ("下个月底", date(2024, 11, 30)),
+            ("12月25日", date(2024, 12, 25)),
+            ("十月一号", date(2025, 10, 1)),
+        ] {
+            assert_eq!(due(text), Some(TaskDue::new(expected, None)), "{text}");
+        }
+    }
+
+    #[test]
+    fn parse_times() {
+        for (text, expected) in [
+            (
+                "明天下午三点",
+                TaskDue::new(date(2024, 10, 17), time(15, 0)),
+            ),
+            (
+                "明天上午10:30",
+                TaskDue::new(date(2024, 10, 17), time(10, 30)),
+            ),
+            ("中午一点", TaskDue::new(today(), time(13, 0))),
+            ("晚上八点半", TaskDue::new(today(), time(20, 30))),
+            ("今晚九点十五分", TaskDue::new(today(), time(21, 15))),
+            ("凌晨两点", TaskDue::new(today(), time(2, 0))),
+            ("下午12点", TaskDue::new(today(), time(12, 0))),
+            // An hour that starts the text or follows a date
+            ("三点开会", TaskDue::new(today(), time(3, 0))),
+            ("明天一点", TaskDue::new(date(2024, 10, 17), time(1, 0))),
+        ] {
+            assert_eq!(due(text), Some(expected), "{text}");
+        }
+    }
+
+    #[test]
+    fn midnight_ends_the_day() {
+        for (text, expected) in [
+            ("晚上十二点", date(2024, 10, 17)),
+            ("今晚十二点", date(2024, 10, 17)),
+            ("明天晚上十二点", date(2024, 10, 18)),
+        ] {
+            assert_eq!(
+                due(text),
+                Some(TaskDue::new(expected, time(0, 0))),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn hours_inside_words_are_no_times() {
+        for text in ["快一点", "写快一点的代码", "差一点"] {
+            let quick_add = parse(text);
+            assert_eq!(quick_add.due, None, "{text}");
+            assert_eq!(quick_add.content, text);
+        }
+        // The day period makes it a time
+        assert_eq!(due("快下午一点"), Some(TaskDue::new(today(), time(13, 0))));
+    }
+
+    #[test]
+    fn parse_recurrences() {
+        for (text, interval, unit) in [
+            ("每天", 1, RecurrenceUnit::Day),
+            ("每周", 1, RecurrenceUnit::Week),
+            ("每两个月", 2, RecurrenceUnit::Month),
+            ("每3年", 3, RecurrenceUnit::Year),
+        ] {
+            assert_eq!(
+                parse(text).recurrence,
+                Some(TaskRecurrence::new(interval, unit)),
+                "{text}"
+            );
+        }
+        let quick_add = parse("每周五健身");
+        assert_eq!(quick_add.content, "健身");
+        assert_eq!(
+            quick_add.recurrence,
+            Some(TaskRecurrence::new(1, RecurrenceUnit::Week))
+        );
+        // `周五` isn't found again as the date
+        assert_eq!(quick_add.due, Some(TaskDue::new(date(2024, 10, 18), None)));
+    }
+
+    #[test]
+    fn content_around_phrases() {
+        let quick_add = parse("和客户明天下午三点开会");
+        assert_eq!(quick_add.content, "和客户开会");
+        assert_eq!(
+            quick_add.due,
+            Some(TaskDue::new(date(2024, 10, 17), time(15, 0)))
+        );
+        // Quoted text is kept as written
+        let quick_add = parse(r#"读 "明天" 三天后"#);
+        assert_eq!(quick_add.content, "读 明天");
+        assert_eq!(quick_add.due, Some(TaskDue::new(date(2024, 10, 19), None)));
+    }
+
+    #[test]
+    fn overflowing_dates_are_no_dates() {
+        for text in ["100000000天后", "4294967295年后"] {
+            let quick_add = parse(text);
+            assert_eq!(quick_add.due, None, "{text}");
+            assert_eq!(quick_add.content, text);
+        }
+        assert_eq!(end_of_month(NaiveDate::MAX, 1), None);
+    }
+}
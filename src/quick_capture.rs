@@ -0,0 +1,241 @@
+// Imports
+use crate::collection_object::{
+    CollectionData, CollectionObject, TodoData, INBOX_TITLE,
+};
+use crate::quick_add::{DateLanguage, QuickAdd};
+use crate::utils::{data_path, write_data_file};
+use crate::{RnApp, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::Local;
+use gtk::{
+    glib, glib::clone, Button, CompositeTemplate, DropDown, Entry, Label, StringList,
+};
+use std::fs::File;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/quick_capture.ui")]
+    pub(crate) struct RnQuickCapture {
+        #[template_child]
+        pub(crate) entry: TemplateChild<Entry>,
+        #[template_child]
+        pub(crate) error_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) collection_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub(crate) add_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnQuickCapture {
+        const NAME: &'static str = "RnQuickCapture";
+        type Type = super::RnQuickCapture;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnQuickCapture {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            self.entry.connect_activate(clone!(
+                #[weak]
+                obj,
+                move |_| {
+                    obj.add_task();
+                }
+            ));
+            self.entry.connect_changed(clone!(
+                #[weak]
+                obj,
+                move |_| {
+                    obj.show_error(None);
+                }
+            ));
+            self.add_button.connect_clicked(clone!(
+                #[weak]
+                obj,
+                move |_| {
+                    obj.add_task();
+                }
+            ));
+        }
+    }
+
+    impl WidgetImpl for RnQuickCapture {}
+    impl WindowImpl for RnQuickCapture {}
+    impl AdwWindowImpl for RnQuickCapture {}
+}
+
+glib::wrapper! {
+    /// A small window that adds a single task, without loading the main window.
+    pub(crate) struct RnQuickCapture(ObjectSubclass<imp::RnQuickCapture>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native,
+                    gtk::Root, gtk::ShortcutManager;
+}
+
+impl RnQuickCapture {
+    pub(crate) fn new(app: &RnApp) -> Self {
+        let quick_capture: Self =
+            glib::Object::builder().property("application", app).build();
+        quick_capture.load_collections();
+        quick_capture
+    }
+
+    fn app(&self) -> RnApp {
+        self.application()
+            .and_downcast::<RnApp>()
+            .expect("`application` should be set in `new`.")
+    }
+
+    /// The titles of the collections, the Inbox first.
+    fn collection_titles(&self) -> Vec<String> {
+        match self.app().unlocked_todo() {
+            Some(todo) => todo
+                .collections()
+                .iter::<CollectionObject>()
+                .filter_map(Result::ok)
+                .map(|collection| collection.title())
+                .collect(),
+            None => {
+                let mut titles: Vec<String> = read_todo_data()
+                    .unwrap_or_default()
+                    .collections
+                    .into_iter()
+                    .filter(|collection_data| !collection_data.inbox)
+                    .map(|collection_data| collection_data.title)
+                    .collect();
+                titles.insert(0, INBOX_TITLE.to_string());
+                titles
+            }
+        }
+    }
+
+    fn load_collections(&self) {
+        let titles = self.collection_titles();
+        let model =
+            StringList::new(&titles.iter().map(String::as_str).collect::<Vec<_>>());
+        let dropdown = self.imp().collection_dropdown.get();
+        dropdown.set_model(Some(&model));
+        dropdown.set_selected(0);
+    }
+
+    fn selected_title(&self) -> Option<String> {
+        self.imp()
+            .collection_dropdown
+            .selected_item()
+            .and_downcast::<gtk::StringObject>()
+            .map(|title| title.string().to_string())
+    }
+
+    fn show_error(&self, error: Option<&str>) {
+        let imp = self.imp();
+        imp.error_label.set_label(error.unwrap_or_default());
+        imp.error_label.set_visible(error.is_some());
+    }
+
+    /// Adds the task of the entry and closes the window, or shows why it failed and
+    /// keeps the text.
+    fn add_task(&self) {
+        let titles = self.collection_titles();
+        let app_settings = self.app().app_settings();
+        let quick_add = QuickAdd::parse(
+            &self.imp().entry.text(),
+            Local::now().date_naive(),
+            &titles,
+            DateLanguage::from_settings(app_settings.as_ref()),
+        );
+        if quick_add.content.is_empty() {
+            return;
+        }
+        // A `#Collection` in the text wins over the selected collection
+        let title = quick_add
+            .collection
+            .clone()
+            .or_else(|| self.selected_title());
+
+        // The main window has the data loaded and saves it when it is closed
+        let result = match self.app().unlocked_todo() {
+            Some(todo) => {
+                add_to_todo(&todo, &quick_add, title.as_deref());
+                Ok(())
+            }
+            None => add_to_data_file(&quick_add, title.as_deref()),
+        };
+        match result {
+            Ok(()) => self.close(),
+            Err(e) => {
+                error!("Failed to add task from quick capture, Err: {e:?}");
+                self.show_error(Some(&format!("The task couldn't be added: {e}")));
+                self.imp().entry.grab_focus();
+            }
+        }
+    }
+}
+
+fn add_to_todo(todo: &RnTodo, quick_add: &QuickAdd, title: Option<&str>) {
+    let collection = title.and_then(|title| {
+        todo.collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .find(|collection| collection.title() == title)
+    });
+    todo.add_task(&quick_add.to_task(), collection.as_ref());
+}
+
+/// Reads the unencrypted data file, empty if it doesn't exist yet.
+fn read_todo_data() -> anyhow::Result<TodoData> {
+    match File::open(data_path()) {
+        Ok(file) => Ok(serde_json::from_reader(file)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TodoData::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Adds the task to the collection with the title in the data file, or to the Inbox.
+fn add_to_data_file(quick_add: &QuickAdd, title: Option<&str>) -> anyhow::Result<()> {
+    let mut todo_data = read_todo_data()?;
+    let collections = &mut todo_data.collections;
+    if !collections
+        .iter()
+        .any(|collection_data| collection_data.inbox)
+    {
+        collections.insert(
+            0,
+            CollectionData {
+                title: INBOX_TITLE.to_string(),
+                inbox: true,
+                ..Default::default()
+            },
+        );
+    }
+    let position = title
+        .and_then(|title| {
+            collections.iter().position(|collection_data| {
+                !collection_data.inbox && collection_data.title == title
+            })
+        })
+        .or_else(|| {
+            collections
+                .iter()
+                .position(|collection_data| collection_data.inbox)
+        })
+        .unwrap_or_default();
+    collections[position]
+        .tasks_data
+        .push(quick_add.to_task().task_data());
+
+    write_data_file(&serde_json::to_vec_pretty(&todo_data)?)
+}
@@ -0,0 +1,404 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::RnTodo;
+use gtk::{glib, prelude::*};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// What a search result leads to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SearchTarget {
+    Task(CollectionObject, TaskObject),
+    Collection(CollectionObject),
+}
+
+impl SearchTarget {
+    /// The collection of the task, or the collection itself.
+    fn collection(&self) -> &CollectionObject {
+        match self {
+            Self::Task(collection, _) | Self::Collection(collection) => collection,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Document {
+    target: SearchTarget,
+    /// The indexed text, compared on sync to find documents that changed
+    text: String,
+    words: Vec<String>,
+}
+
+/// An inverted index over the tasks and collections of all collections.
+///
+/// Words are matched by prefix, so results can be shown while a word is still typed.
+/// Characters of scripts that are written without spaces, like Chinese, are indexed
+/// one by one so that any part of a sentence can be found.
+#[derive(Debug, Default)]
+pub(crate) struct SearchIndex {
+    /// Documents by id, `None` for ids that are free to be reused
+    documents: Vec<Option<Document>>,
+    free_ids: Vec<usize>,
+    ids: HashMap<glib::Object, usize>,
+    postings: BTreeMap<String, BTreeSet<usize>>,
+    /// The tasks that are indexed with each collection
+    tasks_of: HashMap<CollectionObject, HashSet<TaskObject>>,
+    /// The collections whose title or tasks changed since the last sync
+    outdated: HashSet<CollectionObject>,
+    /// Whether collections were added, removed or moved since the last sync
+    all_outdated: bool,
+}
+
+/// Whether the character belongs to a script that is written without spaces between words.
+pub(crate) fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}' // Hiragana and Katakana
+        | '\u{3400}'..='\u{4dbf}' // CJK Extension A
+        | '\u{4e00}'..='\u{9fff}' // CJK Unified Ideographs
+        | '\u{ac00}'..='\u{d7af}' // Hangul Syllables
+        | '\u{f900}'..='\u{faff}' // CJK Compatibility Ideographs
+    )
+}
+
+/// Splits text into lowercase words.
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if is_unspaced_script(c) {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            words.push(c.to_string());
+        } else if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+impl SearchIndex {
+    /// Reindexes the collection and its tasks on the next sync.
+    pub(crate) fn invalidate(&mut self, collection: &CollectionObject) {
+        self.outdated.insert(collection.clone());
+    }
+
+    /// Reindexes all collections on the next sync.
+    pub(crate) fn invalidate_all(&mut self) {
+        self.all_outdated = true;
+    }
+
+    /// Brings the index up to date with the tasks of `todo`, reindexing only the
+    /// collections that changed.
+    pub(crate) fn sync(&mut self, todo: &RnTodo) {
+        self.sync_with(
+            todo.collections()
+                .iter::<CollectionObject>()
+                .filter_map(Result::ok),
+        );
+    }
+
+    fn sync_with(&mut self, collections: impl Iterator<Item = CollectionObject>) {
+        if !std::mem::take(&mut self.all_outdated) {
+            for collection in std::mem::take(&mut self.outdated) {
+                self.sync_collection(&collection);
+            }
+            return;
+        }
+        self.outdated.clear();
+
+        let collections: HashSet<CollectionObject> = collections.collect();
+        for collection in &collections {
+            self.sync_collection(collection);
+        }
+        // Removed collections, with their tasks
+        let removed: Vec<CollectionObject> = self
+            .tasks_of
+            .keys()
+            .filter(|collection| !collections.contains(*collection))
+            .cloned()
+            .collect();
+        for collection in removed {
+            for task_object in self.tasks_of.remove(&collection).unwrap_or_default() {
+                self.remove_task(&collection, &task_object);
+            }
+            self.remove(collection.upcast_ref());
+        }
+    }
+
+    fn sync_collection(&mut self, collection: &CollectionObject) {
+        self.update(
+            collection.upcast_ref(),
+            SearchTarget::Collection(collection.clone()),
+            collection.title(),
+        );
+
+        let mut seen = HashSet::new();
+        for task_object in collection
+            .tasks()
+            .iter::<TaskObject>()
+            .filter_map(Result::ok)
+        {
+            let text = format!("{}\n{}", task_object.content(), task_object.notes());
+            self.update(
+                task_object.upcast_ref(),
+                SearchTarget::Task(collection.clone(), task_object.clone()),
+                text,
+            );
+            seen.insert(task_object);
+        }
+
+        // Tasks that left the collection, the ones that moved to another collection
+        // are indexed again with it
+        let indexed = self.tasks_of.insert(collection.clone(), seen.clone());
+        for task_object in indexed.unwrap_or_default().difference(&seen) {
+            self.remove_task(collection, task_object);
+        }
+    }
+
+    /// Removes the task, unless it was indexed again with another collection.
+    fn remove_task(&mut self, collection: &CollectionObject, task_object: &TaskObject) {
+        let moved = self
+            .ids
+            .get(task_object.upcast_ref::<glib::Object>())
+            .and_then(|id| self.documents[*id].as_ref())
+            .is_some_and(|document| document.target.collection() != collection);
+        if !moved {
+            self.remove(task_object.upcast_ref());
+        }
+    }
+
+    fn update(&mut self, object: &glib::Object, target: SearchTarget, text: String) {
+        if let Some(document) = self
+            .ids
+            .get(object)
+            .and_then(|id| self.documents[*id].as_ref())
+        {
+            // Tasks that moved to another collection need a new target
+            if document.text == text && document.target == target {
+                return;
+            }
+        }
+        self.remove(object);
+
+        let words = tokenize(&text);
+        let id = self.free_ids.pop().unwrap_or(self.documents.len());
+        for word in &words {
+            self.postings.entry(word.clone()).or_default().insert(id);
+        }
+        let document = Some(Document {
+            target,
+            text,
+            words,
+        });
+        if id == self.documents.len() {
+            self.documents.push(document);
+        } else {
+            self.documents[id] = document;
+        }
+        self.ids.insert(object.clone(), id);
+    }
+
+    fn remove(&mut self, object: &glib::Object) {
+        let Some(id) = self.ids.remove(object) else {
+            return;
+        };
+        if let Some(document) = self.documents[id].take() {
+            for word in document.words {
+                if let Some(ids) = self.postings.get_mut(&word) {
+                    ids.remove(&id);
+                    if ids.is_empty() {
+                        self.postings.remove(&word);
+                    }
+                }
+            }
+        }
+        self.free_ids.push(id);
+    }
+
+    /// The ids of documents with a word that starts with `prefix`.
+    fn prefix_matches(&self, prefix: &str) -> BTreeSet<usize> {
+        self.postings
+            .range(prefix.to_string()..)
+            .take_while(|(word, _)| word.starts_with(prefix))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    /// Finds documents that contain every word of the query, collections and open tasks first.
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<SearchTarget> {
+        let terms = tokenize(query);
+        let Some((first, rest)) = terms.split_first() else {
+            return Vec::new();
+        };
+
+        let mut ids = self.prefix_matches(first);
+        for term in rest {
+            if ids.is_empty() {
+                break;
+            }
+            let matches = self.prefix_matches(term);
+            ids.retain(|id| matches.contains(id));
+        }
+
+        let mut targets: Vec<SearchTarget> = ids
+            .into_iter()
+            .filter_map(|id| self.documents[id].as_ref())
+            .map(|document| document.target.clone())
+            .collect();
+        targets.sort_by_key(|target| match target {
+            SearchTarget::Collection(_) => 0,
+            SearchTarget::Task(_, task_object) if !task_object.is_completed() => 1,
+            SearchTarget::Task(_, _) => 2,
+        });
+        targets.truncate(limit);
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gtk::gio;
+
+    fn task(content: &str) -> TaskObject {
+        TaskObject::new(false, content.to_string())
+    }
+
+    fn collection(title: &str, tasks: &[&TaskObject]) -> CollectionObject {
+        let store = gio::ListStore::new::<TaskObject>();
+        for task_object in tasks {
+            store.append(*task_object);
+        }
+        CollectionObject::new(title, store)
+    }
+
+    fn synced(collections: &[&CollectionObject]) -> SearchIndex {
+        let mut index = SearchIndex::default();
+        index.invalidate_all();
+        index.sync_with(collections.iter().map(|collection| (*collection).clone()));
+        index
+    }
+
+    /// The titles of the collections and the contents of the tasks that are found.
+    fn found(index: &SearchIndex, query: &str) -> Vec<String> {
+        index
+            .search(query, 10)
+            .into_iter()
+            .map(|target| match target {
+                SearchTarget::Task(_, task_object) => task_object.content(),
+                SearchTarget::Collection(collection) => collection.title(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_words() {
+        assert_eq!(
+            tokenize("Call vendor, re: Q3-report!"),
+            ["call", "vendor", "re", "q3", "report"]
+        );
+        assert_eq!(tokenize("ÜBER Café"), ["über", "café"]);
+        assert!(tokenize(" ,.- ").is_empty());
+    }
+
+    #[test]
+    fn tokenize_scripts_without_spaces_by_character() {
+        assert_eq!(
+            tokenize("明天开会meeting2点"),
+            ["明", "天", "开", "会", "meeting2", "点"]
+        );
+        assert_eq!(tokenize("カレー 회의"), ["カ", "レ", "ー", "회", "의"]);
+    }
+
+    #[test]
+    fn search_words_by_prefix() {
+        let call = task("Call vendor");
+        let meeting = task("明天开会");
+        let work = collection("Work", &[&call, &meeting]);
+        let index = synced(&[&work]);
+
+        assert_eq!(found(&index, "ven"), ["Call vendor"]);
+        assert_eq!(found(&index, "CALL ven"), ["Call vendor"]);
+        assert!(found(&index, "vendor invoice").is_empty());
+        assert!(found(&index, "endor").is_empty());
+        assert_eq!(found(&index, "开会"), ["明天开会"]);
+        assert_eq!(found(&index, "wo"), ["Work"]);
+        assert!(found(&index, "").is_empty());
+    }
+
+    #[test]
+    fn collections_then_open_then_completed_tasks() {
+        let paid = task("Vendor paid");
+        paid.set_completed(true);
+        let call = task("Call vendor");
+        let vendors = collection("Vendors", &[&paid, &call]);
+        let index = synced(&[&vendors]);
+
+        assert_eq!(
+            found(&index, "vendor"),
+            ["Vendors", "Call vendor", "Vendor paid"]
+        );
+        assert_eq!(index.search("vendor", 2).len(), 2);
+    }
+
+    #[test]
+    fn only_invalidated_collections_are_reindexed() {
+        let call = task("Call vendor");
+        let plan = task("Plan trip");
+        let work = collection("Work", &[&call]);
+        let home = collection("Home", &[&plan]);
+        let mut index = synced(&[&work, &home]);
+
+        call.set_content("Email vendor");
+        plan.set_content("Book trip");
+        index.invalidate(&work);
+        index.sync_with([work.clone(), home.clone()].into_iter());
+        assert_eq!(found(&index, "email"), ["Email vendor"]);
+        assert!(found(&index, "call").is_empty());
+        // Home wasn't invalidated, so it still has the old text
+        assert_eq!(found(&index, "plan"), ["Book trip"]);
+
+        // Removed tasks are dropped
+        work.tasks().remove(0);
+        index.invalidate(&work);
+        index.sync_with([work.clone(), home.clone()].into_iter());
+        assert!(found(&index, "vendor").is_empty());
+    }
+
+    #[test]
+    fn moved_tasks_and_removed_collections() {
+        let call = task("Call vendor");
+        let work = collection("Work", &[&call]);
+        let home = collection("Home", &[]);
+        let mut index = synced(&[&work, &home]);
+
+        // The task moves to Home, in whichever order the collections are synced
+        work.tasks().remove(0);
+        home.tasks().append(&call);
+        index.invalidate(&home);
+        index.invalidate(&work);
+        index.sync_with([work.clone(), home.clone()].into_iter());
+        assert_eq!(
+            index.search("call", 10),
+            [SearchTarget::Task(home.clone(), call.clone())]
+        );
+
+        // Removing Work keeps the task that moved away from it
+        index.invalidate_all();
+        index.sync_with([home.clone()].into_iter());
+        assert!(found(&index, "work").is_empty());
+        assert_eq!(found(&index, "call"), ["Call vendor"]);
+
+        index.invalidate_all();
+        index.sync_with(std::iter::empty());
+        assert!(found(&index, "call").is_empty());
+        assert!(found(&index, "home").is_empty());
+        assert!(index.postings.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 // Imports
+use crate::views::SmartFilter;
 use crate::{FilterPaneRow, FilterType, RnAppMenu, RnAppWindow};
 use adw::{prelude::*, subclass::prelude::*};
-use gtk::{glib, glib::clone, Button, CompositeTemplate, FlowBox, Widget};
+use gtk::{glib, glib::clone, Button, CompositeTemplate, FlowBox, FlowBoxChild, Widget};
 mod imp {
 
     use super::*;
@@ -85,7 +86,7 @@ impl RnSidebar {
     pub(crate) fn filters_flow(&self) -> FlowBox {
         self.imp().filters_flow.get()
     }
-    pub(crate) fn filters_flow_init(&self){
+    pub(crate) fn filters_flow_init(&self, appwindow: &RnAppWindow) {
         let filters_flow=self.filters_flow();
         let inbox_filter = FilterPaneRow::new(FilterType::INBOX);
         let today_filter = FilterPaneRow::new(FilterType::TODAY);
@@ -99,17 +100,103 @@ impl RnSidebar {
         filters_flow.append (&labels_filter);
         filters_flow.append (&pinboard_filter);
         filters_flow.append (&completed_filter);
-        inbox_filter.init();
-        today_filter.init();
-        scheduled_filter.init();
-        labels_filter.init();
-        pinboard_filter.init();
-        completed_filter.init();
-        // filters_flow.child_activated.connect ((child) => {
-        //     let filter = (Layouts.FilterPaneRow) child;
-        //     Services.EventBus.get_default ().pane_selected (PaneType.FILTER, filter.filter_type.to_string ());
-        // });
+        for filter in [
+            &inbox_filter,
+            &today_filter,
+            &scheduled_filter,
+            &labels_filter,
+            &pinboard_filter,
+            &completed_filter,
+        ] {
+            filter.init();
+        }
+        self.load_smart_filters(appwindow);
+        if let Some(app_settings) = appwindow.app().app_settings() {
+            app_settings.connect_changed(
+                Some("smart-filters"),
+                clone!(
+                    #[weak(rename_to = sidebar)]
+                    self,
+                    #[weak]
+                    appwindow,
+                    move |_, _| {
+                        sidebar.load_smart_filters(&appwindow);
+                    }
+                ),
+            );
+        }
+        appwindow.todo().connect_tasks_changed(clone!(
+            #[weak(rename_to = sidebar)]
+            self,
+            move |todo| {
+                for filter in sidebar.filter_rows() {
+                    filter.update_count(todo);
+                }
+            }
+        ));
+        filters_flow.connect_child_activated(clone!(
+            #[weak]
+            appwindow,
+            move |_, child| {
+                let Some(filter) = child.child().and_downcast::<FilterPaneRow>() else {
+                    return;
+                };
+                if let Some(smart_filter) = filter.smart_filter() {
+                    appwindow.filter_view().show_filter(Some(&smart_filter));
+                    appwindow.views_stack().set_visible_child_name("filter_page");
+                } else if let Some(page_name) =
+                    filter.filter_type().and_then(|filter_type| filter_type.page_name())
+                {
+                    if filter.filter_type() == Some(FilterType::INBOX) {
+                        let todo = appwindow.todo();
+                        if let Some(inbox) = todo.inbox() {
+                            todo.show_collection(&inbox);
+                        }
+                    }
+                    appwindow.views_stack().set_visible_child_name(page_name);
+                }
+            }
+        ));
+    }
 
+    /// The tiles of the built-in and the smart filters.
+    fn filter_rows(&self) -> Vec<FilterPaneRow> {
+        let mut rows = Vec::new();
+        let mut child = self.filters_flow().first_child();
+        while let Some(flow_child) = child {
+            if let Some(row) = flow_child
+                .downcast_ref::<FlowBoxChild>()
+                .and_then(|flow_child| flow_child.child())
+                .and_downcast::<FilterPaneRow>()
+            {
+                rows.push(row);
+            }
+            child = flow_child.next_sibling();
+        }
+        rows
+    }
+
+    /// Replaces the smart filter tiles with the filters saved in the settings.
+    fn load_smart_filters(&self, appwindow: &RnAppWindow) {
+        let filters_flow = self.filters_flow();
+        for row in self.filter_rows() {
+            if row.smart_filter().is_some() {
+                if let Some(flow_child) = row.parent() {
+                    filters_flow.remove(&flow_child);
+                }
+            }
+        }
+
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            return;
+        };
+        let todo = appwindow.todo();
+        for smart_filter in SmartFilter::load_all(&app_settings) {
+            let row = FilterPaneRow::new_smart(smart_filter);
+            filters_flow.append(&row);
+            row.init();
+            row.update_count(&todo);
+        }
     }
     pub(crate) fn right_close_button(&self) -> Button {
         self.imp().right_close_button.get()
@@ -129,7 +216,7 @@ impl RnSidebar {
         imp.appmenu.get().init(appwindow);
         self.filters_flow().set_min_children_per_line(2);
         self.filters_flow().set_max_children_per_line(2);
-        self.filters_flow_init();
+        self.filters_flow_init(appwindow);
         // imp.workspacebrowser.get().init(appwindow);
         // imp.settings_panel.get().init(appwindow);
 
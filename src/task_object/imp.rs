@@ -5,7 +5,7 @@ use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
-use super::TaskData;
+use super::{TaskData, TaskDue, TaskRecurrence, TaskReminders, TaskSubtasks};
 
 // Object holding the state
 #[derive(Properties, Debug, Default)]
@@ -13,6 +13,20 @@ use super::TaskData;
 pub struct TaskObject {
     #[property(name = "completed", get, set, type = bool, member = completed)]
     #[property(name = "content", get, set, type = String, member = content)]
+    #[property(name = "notes", get, set, type = String, member = notes)]
+    #[property(name = "due", get, set, type = Option<TaskDue>, member = due)]
+    #[property(name = "priority", get, set, type = u8, member = priority)]
+    #[property(name = "section", get, set, type = String, member = section)]
+    #[property(
+        name = "recurrence",
+        get,
+        set,
+        type = Option<TaskRecurrence>,
+        member = recurrence
+    )]
+    #[property(name = "subtasks", get, set, type = TaskSubtasks, member = subtasks)]
+    #[property(name = "attachments", get, set, type = Vec<String>, member = attachments)]
+    #[property(name = "reminders", get, set, type = TaskReminders, member = reminders)]
     pub data: RefCell<TaskData>,
 }
 
@@ -25,4 +39,23 @@ impl ObjectSubclass for TaskObject {
 
 // Trait shared by all GObjects
 #[glib::derived_properties]
-impl ObjectImpl for TaskObject {}
+impl ObjectImpl for TaskObject {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        // Record when the task gets completed
+        self.obj().connect_completed_notify(|task_object| {
+            let mut data = task_object.imp().data.borrow_mut();
+            if !data.completed {
+                data.completed_at = None;
+            } else if data.completed_at.is_none() {
+                data.completed_at = Some(chrono::Local::now());
+            }
+        });
+        // Record when the task was last changed
+        self.obj().connect_notify_local(None, |task_object, _| {
+            task_object.imp().data.borrow_mut().modified_at =
+                Some(chrono::Local::now());
+        });
+    }
+}
@@ -1,20 +1,26 @@
 mod imp;
 
 use adw::subclass::prelude::*;
+use chrono::{DateTime, Days, Local, Months, NaiveDate, NaiveTime};
 use glib::Object;
 use gtk::glib;
 use serde::{Deserialize, Serialize};
 
+/// The highest priority a task can have, 0 is no priority.
+pub const MAX_PRIORITY: u8 = 3;
+
 glib::wrapper! {
     pub struct TaskObject(ObjectSubclass<imp::TaskObject>);
 }
 
 impl TaskObject {
     pub fn new(completed: bool, content: String) -> Self {
-        Object::builder()
+        let task_object: Self = Object::builder()
             .property("completed", completed)
             .property("content", content)
-            .build()
+            .build();
+        task_object.imp().data.borrow_mut().created_at = Some(Local::now());
+        task_object
     }
 
     pub fn is_completed(&self) -> bool {
@@ -26,7 +32,96 @@ impl TaskObject {
     }
 
     pub fn from_task_data(task_data: TaskData) -> Self {
-        Self::new(task_data.completed, task_data.content)
+        let task_object = Self::new(task_data.completed, task_data.content.clone());
+        task_object.imp().data.replace(task_data);
+        task_object
+    }
+
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        self.imp().data.borrow().due.map(|due| due.date)
+    }
+
+    /// When the task was completed, `None` for open tasks and tasks completed before it was recorded.
+    pub fn completed_at(&self) -> Option<DateTime<Local>> {
+        self.imp().data.borrow().completed_at
+    }
+
+    /// When the task was created, `None` for tasks created before it was recorded.
+    pub fn created_at(&self) -> Option<DateTime<Local>> {
+        self.imp().data.borrow().created_at
+    }
+
+    /// When the task was last changed, `None` for tasks unchanged since it was recorded.
+    pub fn modified_at(&self) -> Option<DateTime<Local>> {
+        self.imp().data.borrow().modified_at
+    }
+
+    /// The lowercase `#tags` in the content of the task, without the `#`.
+    pub fn labels(&self) -> Vec<String> {
+        self.imp()
+            .data
+            .borrow()
+            .content
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix('#'))
+            .map(|tag| {
+                tag.trim_end_matches(|c: char| c.is_ascii_punctuation())
+                    .to_lowercase()
+            })
+            .filter(|label| !label.is_empty())
+            .collect()
+    }
+
+    /// Appends `#label` to the content, unless the task already has the label.
+    pub fn add_label(&self, label: &str) {
+        if self.labels().iter().any(|l| l == &label.to_lowercase()) {
+            return;
+        }
+        self.set_content(format!("{} #{label}", self.content()));
+    }
+
+    /// Removes every `#label` from the content, with the spaces before it, or after it
+    /// at the start of a line. The rest of the content is kept as it is.
+    pub fn remove_label(&self, label: &str) {
+        let label = label.to_lowercase();
+        let content = self.content();
+        let mut kept = String::with_capacity(content.len());
+        let mut rest = content.as_str();
+        let mut skip_spaces = false;
+        while !rest.is_empty() {
+            let start = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let end = rest[start..]
+                .find(char::is_whitespace)
+                .map_or(rest.len(), |i| start + i);
+            let (mut space, word) = (&rest[..start], &rest[start..end]);
+            if skip_spaces {
+                space = space.trim_start_matches([' ', '\t']);
+            }
+            let is_label = word.strip_prefix('#').is_some_and(|tag| {
+                tag.trim_end_matches(|c: char| c.is_ascii_punctuation())
+                    .to_lowercase()
+                    == label
+            });
+            if is_label {
+                let before = space.trim_end_matches([' ', '\t']);
+                skip_spaces = before.len() == space.len();
+                kept.push_str(before);
+            } else {
+                skip_spaces = false;
+                kept.push_str(space);
+                kept.push_str(word);
+            }
+            rest = &rest[end..];
+        }
+        self.set_content(kept);
+    }
+
+    /// Moves the task to another day, keeping its time.
+    pub fn reschedule(&self, date: NaiveDate) {
+        let time = self.due().and_then(|due| due.time);
+        self.set_due(Some(TaskDue::new(date, time)));
     }
 }
 
@@ -34,4 +129,189 @@ impl TaskObject {
 pub struct TaskData {
     pub completed: bool,
     pub content: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub due: Option<TaskDue>,
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub section: String,
+    #[serde(default)]
+    pub recurrence: Option<TaskRecurrence>,
+    #[serde(default)]
+    pub subtasks: TaskSubtasks,
+    /// The URIs of attached files
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    #[serde(default)]
+    pub reminders: TaskReminders,
+    /// When a property of the task was last changed
+    #[serde(default)]
+    pub modified_at: Option<DateTime<Local>>,
+}
+
+/// An item of the checklist of a task.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskSubtask {
+    pub title: String,
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/// The checklist of a task, in its order.
+#[derive(glib::Boxed, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[boxed_type(name = "TodoTaskSubtasks")]
+#[serde(transparent)]
+pub struct TaskSubtasks(pub Vec<TaskSubtask>);
+
+/// When to be reminded of a task, sorted from the earliest.
+#[derive(glib::Boxed, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[boxed_type(name = "TodoTaskReminders")]
+#[serde(transparent)]
+pub struct TaskReminders(pub Vec<DateTime<Local>>);
+
+/// The day a task is due, optionally at a time of that day.
+#[derive(glib::Boxed, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[boxed_type(name = "TodoTaskDue", nullable)]
+pub struct TaskDue {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+}
+
+impl TaskDue {
+    pub fn new(date: NaiveDate, time: Option<NaiveTime>) -> Self {
+        Self { date, time }
+    }
+}
+
+/// How a task repeats, the next task is due one interval after the completed one.
+#[derive(glib::Boxed, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[boxed_type(name = "TodoTaskRecurrence", nullable)]
+pub struct TaskRecurrence {
+    pub interval: u32,
+    pub unit: RecurrenceUnit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TaskRecurrence {
+    pub fn new(interval: u32, unit: RecurrenceUnit) -> Self {
+        Self {
+            interval: interval.max(1),
+            unit,
+        }
+    }
+
+    /// The date one interval after `date`, `None` when it is out of range.
+    pub fn next(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.nth(date, 1)
+    }
+
+    /// The date `count` intervals after `date`, `None` when it is out of range.
+    ///
+    /// Unlike repeating [TaskRecurrence::next], this keeps the day of the month of
+    /// `date` after shorter months.
+    pub fn nth(&self, date: NaiveDate, count: u32) -> Option<NaiveDate> {
+        let interval = self.interval.checked_mul(count)?;
+        match self.unit {
+            RecurrenceUnit::Day => {
+                date.checked_add_days(Days::new(u64::from(interval)))
+            }
+            RecurrenceUnit::Week => {
+                date.checked_add_days(Days::new(7 * u64::from(interval)))
+            }
+            RecurrenceUnit::Month => date.checked_add_months(Months::new(interval)),
+            RecurrenceUnit::Year => {
+                date.checked_add_months(Months::new(interval.checked_mul(12)?))
+            }
+        }
+    }
+
+    /// Describes the recurrence, like "every 2 weeks".
+    pub fn description(&self) -> String {
+        let unit = match self.unit {
+            RecurrenceUnit::Day => "day",
+            RecurrenceUnit::Week => "week",
+            RecurrenceUnit::Month => "month",
+            RecurrenceUnit::Year => "year",
+        };
+        if self.interval == 1 {
+            format!("every {unit}")
+        } else {
+            format!("every {} {unit}s", self.interval)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The content after removing the label from a task with `content`.
+    fn without_label(content: &str, label: &str) -> String {
+        let task_object = TaskObject::new(false, content.to_string());
+        task_object.remove_label(label);
+        task_object.content()
+    }
+
+    #[test]
+    fn remove_label_at_the_start_or_end() {
+        assert_eq!(without_label("#ops Call vendor", "ops"), "Call vendor");
+        assert_eq!(without_label("Call vendor #ops", "ops"), "Call vendor");
+        assert_eq!(without_label("Call vendor #Ops.", "OPS"), "Call vendor");
+        assert_eq!(without_label("#ops", "ops"), "");
+    }
+
+    #[test]
+    fn remove_every_occurrence_of_the_label() {
+        assert_eq!(
+            without_label("#ops Call #ops vendor #OPS", "ops"),
+            "Call vendor"
+        );
+    }
+
+    #[test]
+    fn remove_label_keeps_longer_labels() {
+        assert_eq!(
+            without_label("#opsteam Call #ops vendor #ops-team", "ops"),
+            "#opsteam Call vendor #ops-team"
+        );
+        assert_eq!(without_label("Call #opsteam", "ops"), "Call #opsteam");
+        assert_eq!(
+            without_label("Mail ops@example.com", "ops"),
+            "Mail ops@example.com"
+        );
+    }
+
+    #[test]
+    fn remove_label_keeps_the_rest_of_the_text() {
+        assert_eq!(
+            without_label("Plan\n#ops review  notes\n\tnext #ops", "ops"),
+            "Plan\nreview  notes\n\tnext"
+        );
+        assert_eq!(without_label("Call  vendor", "ops"), "Call  vendor");
+    }
+
+    #[test]
+    fn add_label_once() {
+        let task_object = TaskObject::new(false, String::from("Call vendor #Ops"));
+        task_object.add_label("ops");
+        task_object.add_label("urgent");
+        task_object.add_label("Urgent");
+        assert_eq!(task_object.content(), "Call vendor #Ops #urgent");
+        assert_eq!(task_object.labels(), ["ops", "urgent"]);
+    }
 }
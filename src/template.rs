@@ -0,0 +1,457 @@
+// Imports
+use crate::collection_object::CollectionData;
+use crate::task_object::TaskRecurrence;
+use chrono::{Local, NaiveDate, TimeDelta};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// A `{name}` that is asked for when a template is used.
+static PLACEHOLDER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{([^{}\s]+)\}").expect("the placeholder pattern is valid")
+});
+
+/// Replaces the `{name}` placeholders that have a value.
+fn fill_placeholders(text: &str, values: &HashMap<String, String>) -> String {
+    PLACEHOLDER_RE
+        .replace_all(text, |captures: &Captures| {
+            values
+                .get(&captures[1])
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+/// A collection that is saved to be created again, with its sections, tasks and
+/// subtasks.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplateData {
+    pub name: String,
+    /// The saved collection, its tasks are open
+    pub collection: CollectionData,
+    /// The due dates are relative to this day, it is moved to the day that is chosen
+    /// when the template is used
+    pub anchor: NaiveDate,
+    #[serde(default)]
+    pub schedule: Option<TemplateSchedule>,
+}
+
+/// When the template is used without asking, see [TemplateData::next_due_run].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplateSchedule {
+    pub recurrence: TaskRecurrence,
+    /// The day of the next run, runs that were missed are made up for
+    pub next_run: NaiveDate,
+    /// The day the runs are counted from, so that they keep its day of the month
+    #[serde(default)]
+    pub start: Option<NaiveDate>,
+    /// The number of runs since `start`
+    #[serde(default)]
+    pub runs: u32,
+    /// The title of the created collection or section, can have placeholders
+    pub title: String,
+    /// The values of the placeholders, `{date}` is the day of the run
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+    /// The title of the collection that receives the tasks as new section, instead of
+    /// creating a collection
+    #[serde(default)]
+    pub section_of: Option<String>,
+}
+
+impl TemplateData {
+    /// Saves the collection as template, anchored at its earliest due date.
+    pub fn from_collection(name: &str, mut collection: CollectionData) -> Self {
+        collection.inbox = false;
+        for task_data in &mut collection.tasks_data {
+            task_data.completed = false;
+            task_data.completed_at = None;
+            task_data.created_at = None;
+            task_data.modified_at = None;
+            for subtask in &mut task_data.subtasks.0 {
+                subtask.completed = false;
+            }
+        }
+        let anchor = collection
+            .tasks_data
+            .iter()
+            .filter_map(|task_data| task_data.due.map(|due| due.date))
+            .min()
+            .unwrap_or_else(|| Local::now().date_naive());
+        Self {
+            name: name.to_string(),
+            collection,
+            anchor,
+            schedule: None,
+        }
+    }
+
+    /// The names of the placeholders, in the order they first appear.
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut texts = vec![self.collection.title.as_str()];
+        texts.extend(self.collection.sections.iter().map(String::as_str));
+        for task_data in &self.collection.tasks_data {
+            texts.push(&task_data.content);
+            texts.push(&task_data.notes);
+            texts.push(&task_data.section);
+            texts.extend(
+                task_data
+                    .subtasks
+                    .0
+                    .iter()
+                    .map(|subtask| subtask.title.as_str()),
+            );
+        }
+        let mut placeholders: Vec<String> = Vec::new();
+        for captures in texts
+            .into_iter()
+            .flat_map(|text| PLACEHOLDER_RE.captures_iter(text))
+        {
+            let name = &captures[1];
+            if !placeholders.iter().any(|placeholder| placeholder == name) {
+                placeholders.push(name.to_string());
+            }
+        }
+        placeholders
+    }
+
+    /// The collection of the template, with the placeholders replaced by `values` and
+    /// the due dates and reminders moved by as many days as the anchor is to `anchor`.
+    ///
+    /// Due dates and reminders that would be out of range are left out.
+    pub fn instantiate(
+        &self,
+        values: &HashMap<String, String>,
+        anchor: NaiveDate,
+    ) -> CollectionData {
+        let replace = |text: &str| fill_placeholders(text, values);
+        let shift: TimeDelta = anchor - self.anchor;
+        let now = Local::now();
+
+        let mut collection = self.collection.clone();
+        collection.title = replace(&collection.title);
+        for section in &mut collection.sections {
+            *section = replace(section);
+        }
+        for task_data in &mut collection.tasks_data {
+            task_data.content = replace(&task_data.content);
+            task_data.notes = replace(&task_data.notes);
+            task_data.section = replace(&task_data.section);
+            for subtask in &mut task_data.subtasks.0 {
+                subtask.title = replace(&subtask.title);
+            }
+            // Dates that would be moved out of range are dropped
+            task_data.due = task_data.due.and_then(|mut due| {
+                due.date = due.date.checked_add_signed(shift)?;
+                Some(due)
+            });
+            task_data.reminders.0 = task_data
+                .reminders
+                .0
+                .iter()
+                .filter_map(|reminder| reminder.checked_add_signed(shift))
+                .collect();
+            task_data.created_at = Some(now);
+        }
+        collection
+    }
+
+    /// The collection of the next run of the schedule if it is due by `today`, the
+    /// schedule then moves on to the run after it.
+    pub fn next_due_run(&mut self, today: NaiveDate) -> Option<CollectionData> {
+        let schedule = self.schedule.as_ref()?;
+        let date = schedule.next_run;
+        if date > today {
+            return None;
+        }
+        let mut values = schedule.values.clone();
+        values.insert(String::from("date"), date.format("%Y-%m-%d").to_string());
+        let title = fill_placeholders(&schedule.title, &values);
+
+        let mut collection = self.instantiate(&values, date);
+        collection.title = title;
+        // A schedule whose next run is out of range stops
+        self.schedule = self.schedule.take().and_then(|mut schedule| {
+            let start = *schedule.start.get_or_insert(date);
+            schedule.runs = schedule.runs.checked_add(1)?;
+            schedule.next_run = schedule.recurrence.nth(start, schedule.runs)?;
+            Some(schedule)
+        });
+        Some(collection)
+    }
+
+    /// The collections of all runs that are due by `today`, or of only the last `limit`
+    /// ones when it isn't 0, and how many older runs were skipped.
+    pub fn due_runs(
+        &mut self,
+        today: NaiveDate,
+        limit: usize,
+    ) -> (Vec<CollectionData>, usize) {
+        let mut runs = VecDeque::new();
+        let mut skipped = 0;
+        while let Some(collection) = self.next_due_run(today) {
+            if limit > 0 && runs.len() == limit {
+                runs.pop_front();
+                skipped += 1;
+            }
+            runs.push_back(collection);
+        }
+        (runs.into(), skipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_object::{
+        RecurrenceUnit, TaskData, TaskDue, TaskReminders, TaskSubtask, TaskSubtasks,
+    };
+    use chrono::{NaiveTime, TimeZone};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn template() -> TemplateData {
+        let task_data = TaskData {
+            content: String::from("Call {contact} at {client}"),
+            notes: String::from("Bring {doc}"),
+            section: String::from("{phase}"),
+            due: Some(TaskDue::new(
+                date(2024, 1, 12),
+                NaiveTime::from_hms_opt(15, 0, 0),
+            )),
+            reminders: TaskReminders(vec![Local
+                .with_ymd_and_hms(2024, 1, 12, 9, 0, 0)
+                .unwrap()]),
+            subtasks: TaskSubtasks(vec![TaskSubtask {
+                title: String::from("{doc} for {client}"),
+                completed: false,
+            }]),
+            ..Default::default()
+        };
+        let untimed = TaskData {
+            content: String::from("Follow up"),
+            due: Some(TaskDue::new(date(2024, 1, 10), None)),
+            ..Default::default()
+        };
+        TemplateData::from_collection(
+            "Onboarding",
+            CollectionData {
+                title: String::from("{client} onboarding"),
+                sections: vec![String::from("{phase}")],
+                tasks_data: vec![task_data, untimed],
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn fill_only_placeholders_with_values() {
+        assert_eq!(
+            fill_placeholders(
+                "{client} kickoff {missing} {not a placeholder} {} {{client}}",
+                &values(&[("client", "Acme")]),
+            ),
+            "Acme kickoff {missing} {not a placeholder} {} {Acme}"
+        );
+    }
+
+    #[test]
+    fn placeholders_in_order_of_appearance() {
+        assert_eq!(
+            template().placeholders(),
+            ["client", "phase", "contact", "doc"]
+        );
+    }
+
+    #[test]
+    fn anchor_is_the_earliest_due_date() {
+        assert_eq!(template().anchor, date(2024, 1, 10));
+    }
+
+    #[test]
+    fn instantiate_fills_placeholders_and_shifts_dates() {
+        let template = template();
+        let collection = template.instantiate(
+            &values(&[
+                ("client", "Acme"),
+                ("phase", "Setup"),
+                ("contact", "Ada"),
+                ("doc", "Contract"),
+            ]),
+            date(2024, 3, 1),
+        );
+        assert_eq!(collection.title, "Acme onboarding");
+        assert_eq!(collection.sections, ["Setup"]);
+
+        let task_data = &collection.tasks_data[0];
+        assert_eq!(task_data.content, "Call Ada at Acme");
+        assert_eq!(task_data.notes, "Bring Contract");
+        assert_eq!(task_data.section, "Setup");
+        assert_eq!(task_data.subtasks.0[0].title, "Contract for Acme");
+        // The task was due two days after the anchor
+        assert_eq!(
+            task_data.due,
+            Some(TaskDue::new(
+                date(2024, 3, 3),
+                NaiveTime::from_hms_opt(15, 0, 0)
+            ))
+        );
+        let shift = date(2024, 3, 1) - date(2024, 1, 10);
+        assert_eq!(
+            task_data.reminders.0,
+            [template.collection.tasks_data[0].reminders.0[0] + shift]
+        );
+        assert_eq!(
+            collection.tasks_data[1].due,
+            Some(TaskDue::new(date(2024, 3, 1), None))
+        );
+    }
+
+    #[test]
+    fn monthly_runs_keep_the_day_of_the_first_run() {
+        let mut template = template();
+        template.schedule = Some(TemplateSchedule {
+            recurrence: TaskRecurrence::new(1, RecurrenceUnit::Month),
+            next_run: date(2024, 1, 31),
+            start: Some(date(2024, 1, 31)),
+            runs: 0,
+            title: String::from("{client} {date}"),
+            values: values(&[("client", "Acme")]),
+            section_of: None,
+        });
+
+        let mut runs = Vec::new();
+        while let Some(collection) = template.next_due_run(date(2024, 5, 1)) {
+            let first_due = collection.tasks_data[1].due.map(|due| due.date);
+            runs.push((collection.title, first_due));
+        }
+        assert_eq!(
+            runs,
+            [
+                (String::from("Acme 2024-01-31"), Some(date(2024, 1, 31))),
+                (String::from("Acme 2024-02-29"), Some(date(2024, 2, 29))),
+                (String::from("Acme 2024-03-31"), Some(date(2024, 3, 31))),
+                (String::from("Acme 2024-04-30"), Some(date(2024, 4, 30))),
+            ]
+        );
+        let schedule = template.schedule.as_ref().unwrap();
+        assert_eq!(schedule.next_run, date(2024, 5, 31));
+        assert_eq!(schedule.runs, 4);
+    }
+
+    #[test]
+    fn schedules_without_start_count_from_the_next_run() {
+        let mut template = template();
+        template.schedule = Some(TemplateSchedule {
+            recurrence: TaskRecurrence::new(1, RecurrenceUnit::Month),
+            next_run: date(2024, 1, 31),
+            start: None,
+            runs: 0,
+            title: String::from("Report"),
+            values: HashMap::new(),
+            section_of: None,
+        });
+        assert!(template.next_due_run(date(2024, 1, 31)).is_some());
+        assert!(template.next_due_run(date(2024, 1, 31)).is_none());
+        let schedule = template.schedule.as_ref().unwrap();
+        assert_eq!(schedule.start, Some(date(2024, 1, 31)));
+        assert_eq!(schedule.next_run, date(2024, 2, 29));
+    }
+
+    #[test]
+    fn schedules_stop_when_the_next_run_is_out_of_range() {
+        let mut template = template();
+        template.schedule = Some(TemplateSchedule {
+            recurrence: TaskRecurrence::new(1, RecurrenceUnit::Year),
+            next_run: date(2024, 1, 1),
+            start: Some(NaiveDate::MAX),
+            runs: 0,
+            title: String::from("Report"),
+            values: HashMap::new(),
+            section_of: None,
+        });
+        assert!(template.next_due_run(date(2024, 1, 1)).is_some());
+        assert!(template.schedule.is_none());
+    }
+
+    #[test]
+    fn dates_moved_out_of_range_are_left_out() {
+        let mut template = template();
+        template.anchor = NaiveDate::MIN;
+        let collection = template.instantiate(&HashMap::new(), NaiveDate::MAX);
+        for task_data in &collection.tasks_data {
+            assert_eq!(task_data.due, None);
+            assert!(task_data.reminders.0.is_empty());
+        }
+
+        // A run of a schedule doesn't fail either
+        template.schedule = Some(TemplateSchedule {
+            recurrence: TaskRecurrence::new(1, RecurrenceUnit::Day),
+            next_run: date(2024, 1, 1),
+            start: None,
+            runs: 0,
+            title: String::from("Report"),
+            values: HashMap::new(),
+            section_of: None,
+        });
+        let collection = template.next_due_run(date(2024, 1, 1)).unwrap();
+        assert_eq!(collection.tasks_data[1].due, None);
+    }
+
+    #[test]
+    fn missed_runs_are_made_up_for_up_to_the_limit() {
+        let schedule = TemplateSchedule {
+            recurrence: TaskRecurrence::new(1, RecurrenceUnit::Week),
+            next_run: date(2024, 1, 1),
+            start: None,
+            runs: 0,
+            title: String::from("Week {date}"),
+            values: HashMap::new(),
+            section_of: None,
+        };
+        let titles = |runs: Vec<CollectionData>| -> Vec<String> {
+            runs.into_iter()
+                .map(|collection| collection.title)
+                .collect()
+        };
+
+        let mut template = template();
+        template.schedule = Some(schedule.clone());
+        let (runs, skipped) = template.due_runs(date(2024, 1, 29), 0);
+        assert_eq!(
+            titles(runs),
+            [
+                "Week 2024-01-01",
+                "Week 2024-01-08",
+                "Week 2024-01-15",
+                "Week 2024-01-22",
+                "Week 2024-01-29",
+            ]
+        );
+        assert_eq!(skipped, 0);
+
+        template.schedule = Some(schedule);
+        let (runs, skipped) = template.due_runs(date(2024, 1, 29), 2);
+        assert_eq!(titles(runs), ["Week 2024-01-22", "Week 2024-01-29"]);
+        assert_eq!(skipped, 3);
+        // The skipped runs are not due anymore
+        assert_eq!(
+            template.schedule.as_ref().unwrap().next_run,
+            date(2024, 2, 5)
+        );
+        let (runs, skipped) = template.due_runs(date(2024, 2, 4), 2);
+        assert!(runs.is_empty());
+        assert_eq!(skipped, 0);
+    }
+}
@@ -0,0 +1,343 @@
+// Imports
+use crate::collection_object::{BoardColumns, CollectionObject};
+use crate::task_object::TaskObject;
+use crate::views::{add_task_drag_source, add_task_drop_target};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::Local;
+use gtk::{
+    gio, glib, glib::clone, CheckButton, CompositeTemplate, Entry, Label, Widget,
+};
+use std::cell::{Cell, RefCell};
+
+/// The width of a column of the board.
+const COLUMN_WIDTH: i32 = 260;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/board.ui")]
+    pub(crate) struct RnTaskBoard {
+        pub(crate) collection: RefCell<Option<CollectionObject>>,
+        /// The filtered and sorted tasks of the collection
+        pub(crate) tasks: RefCell<Option<gio::ListModel>>,
+        pub(crate) refresh_queued: Cell<bool>,
+
+        #[template_child]
+        pub(crate) columns_box: TemplateChild<gtk::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnTaskBoard {
+        const NAME: &'static str = "RnTaskBoard";
+        type Type = super::RnTaskBoard;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnTaskBoard {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnTaskBoard {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnTaskBoard(ObjectSubclass<imp::RnTaskBoard>)
+        @extends Widget;
+}
+
+impl Default for RnTaskBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A column of the board, tasks dropped onto it are changed to belong to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BoardColumn {
+    /// Tasks of the section, the empty section holds tasks without one
+    Section(String),
+    Open,
+    Completed,
+}
+
+impl BoardColumn {
+    fn of_task(task_object: &TaskObject, columns: BoardColumns) -> Self {
+        match columns {
+            BoardColumns::Section => Self::Section(task_object.section()),
+            BoardColumns::Status if task_object.is_completed() => Self::Completed,
+            BoardColumns::Status => Self::Open,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Section(section) if section.is_empty() => String::from("No Section"),
+            Self::Section(section) => section.clone(),
+            Self::Open => String::from("To Do"),
+            Self::Completed => String::from("Done"),
+        }
+    }
+
+    fn apply(&self, task_object: &TaskObject) {
+        match self {
+            Self::Section(section) => task_object.set_section(section.as_str()),
+            Self::Open => task_object.set_completed(false),
+            Self::Completed => task_object.set_completed(true),
+        }
+    }
+}
+
+impl RnTaskBoard {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Shows the tasks of the collection, in the order of the model.
+    pub(crate) fn set_tasks(
+        &self,
+        collection: Option<&CollectionObject>,
+        tasks: Option<&gio::ListModel>,
+    ) {
+        self.imp().collection.replace(collection.cloned());
+        self.imp().tasks.replace(tasks.cloned());
+        self.queue_refresh();
+    }
+
+    /// Rebuilds the board once the main loop is idle, so that it isn't rebuilt during a drop.
+    pub(crate) fn queue_refresh(&self) {
+        if self.imp().refresh_queued.replace(true) {
+            return;
+        }
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = board)]
+            self,
+            move || {
+                board.imp().refresh_queued.set(false);
+                board.refresh();
+            }
+        ));
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        while let Some(child) = imp.columns_box.first_child() {
+            imp.columns_box.remove(&child);
+        }
+        let Some(collection) = imp.collection.borrow().clone() else {
+            return;
+        };
+        let tasks: Vec<TaskObject> = imp
+            .tasks
+            .borrow()
+            .as_ref()
+            .map(|tasks| tasks.iter::<TaskObject>().filter_map(Result::ok).collect())
+            .unwrap_or_default();
+
+        let board_columns = collection.view_options().board_columns;
+        let columns = match board_columns {
+            BoardColumns::Section => std::iter::once(String::new())
+                .chain(collection.sections())
+                .map(BoardColumn::Section)
+                .collect(),
+            BoardColumns::Status => vec![BoardColumn::Open, BoardColumn::Completed],
+        };
+        for column in columns {
+            let column_tasks: Vec<&TaskObject> = tasks
+                .iter()
+                .filter(|task_object| {
+                    BoardColumn::of_task(task_object, board_columns) == column
+                })
+                .collect();
+            self.add_column(column, &column_tasks);
+        }
+
+        if board_columns == BoardColumns::Section {
+            let entry = Entry::builder()
+                .placeholder_text("New Section")
+                .secondary_icon_name("list-add-symbolic")
+                .width_request(COLUMN_WIDTH)
+                .valign(gtk::Align::Start)
+                .build();
+            let add_section = clone!(
+                #[weak]
+                collection,
+                move |entry: &Entry| {
+                    collection.add_section(entry.text().trim());
+                }
+            );
+            entry.connect_activate(add_section.clone());
+            entry.connect_icon_release(move |entry, _| add_section(entry));
+            imp.columns_box.append(&entry);
+        }
+    }
+
+    fn add_column(&self, column: BoardColumn, tasks: &[&TaskObject]) {
+        let column_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .width_request(COLUMN_WIDTH)
+            .build();
+        column_box.add_css_class("board_column");
+
+        let header = gtk::Box::builder().spacing(6).build();
+        let title = Label::builder()
+            .label(column.title())
+            .xalign(0.0)
+            .hexpand(true)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build();
+        title.add_css_class("heading");
+        header.append(&title);
+        let count = Label::new(Some(&tasks.len().to_string()));
+        count.add_css_class("dim-label");
+        header.append(&count);
+        column_box.append(&header);
+
+        for task_object in tasks {
+            column_box.append(&self.create_card(task_object, &column));
+        }
+
+        // Dropping below the cards moves the task to the end of the column
+        add_task_drop_target(
+            &column_box,
+            clone!(
+                #[weak(rename_to = board)]
+                self,
+                move |task_object| {
+                    board.drop_task(&task_object, &column, None);
+                }
+            ),
+        );
+        self.imp().columns_box.append(&column_box);
+    }
+
+    fn create_card(&self, task_object: &TaskObject, column: &BoardColumn) -> gtk::Box {
+        let check_button = CheckButton::builder()
+            .valign(gtk::Align::Start)
+            .can_focus(false)
+            .build();
+        task_object
+            .bind_property("completed", &check_button, "active")
+            .bidirectional()
+            .sync_create()
+            .build();
+        let title = Label::builder()
+            .label(task_object.content())
+            .wrap(true)
+            .xalign(0.0)
+            .hexpand(true)
+            .build();
+        let top = gtk::Box::builder().spacing(6).build();
+        top.append(&check_button);
+        top.append(&title);
+
+        let card = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .build();
+        card.add_css_class("card");
+        card.add_css_class("board_card");
+        card.append(&top);
+
+        let chips = card_chips(task_object);
+        if chips.first_child().is_some() {
+            card.append(&chips);
+        }
+
+        add_task_drag_source(&card, task_object);
+        // Dropping onto a card places the task above it
+        let column = column.clone();
+        add_task_drop_target(
+            &card,
+            clone!(
+                #[weak(rename_to = board)]
+                self,
+                #[weak]
+                task_object,
+                move |dropped_task| {
+                    board.drop_task(&dropped_task, &column, Some(&task_object));
+                }
+            ),
+        );
+        card
+    }
+
+    /// Moves the task into the column, above the task `before` or else to the end.
+    fn drop_task(
+        &self,
+        task_object: &TaskObject,
+        column: &BoardColumn,
+        before: Option<&TaskObject>,
+    ) {
+        let Some(collection) = self.imp().collection.borrow().clone() else {
+            return;
+        };
+        // Only tasks of this collection can be arranged on its board
+        let tasks = collection.tasks();
+        let Some(position) = tasks.find(task_object) else {
+            return;
+        };
+        if before == Some(task_object) {
+            return;
+        }
+
+        column.apply(task_object);
+        tasks.remove(position);
+        let target = before
+            .and_then(|before| tasks.find(before))
+            .unwrap_or(tasks.n_items());
+        tasks.insert(target, task_object);
+    }
+}
+
+/// The due date, priority and labels of the task.
+fn card_chips(task_object: &TaskObject) -> gtk::Box {
+    let chips = gtk::Box::builder().spacing(4).build();
+    let add_chip = |text: &str, css_class: Option<&str>| {
+        let chip = Label::new(Some(text));
+        chip.add_css_class("board_chip");
+        if let Some(css_class) = css_class {
+            chip.add_css_class(css_class);
+        }
+        chips.append(&chip);
+    };
+
+    if let Some(due) = task_object.due() {
+        let today = Local::now().date_naive();
+        let mut text = if due.date == today {
+            String::from("Today")
+        } else {
+            due.date.format("%b %-d").to_string()
+        };
+        if let Some(time) = due.time {
+            text += &time.format(" %H:%M").to_string();
+        }
+        let overdue = due.date < today && !task_object.is_completed();
+        add_chip(&text, overdue.then_some("error"));
+    }
+    if task_object.priority() > 0 {
+        add_chip(&format!("P{}", task_object.priority()), Some("warning"));
+    }
+    for label in task_object.labels() {
+        add_chip(&format!("#{label}"), Some("accent"));
+    }
+    chips
+}
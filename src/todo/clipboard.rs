@@ -0,0 +1,196 @@
+// Imports
+use super::RnTodo;
+use crate::collection_object::CollectionObject;
+use crate::quick_add;
+use crate::task_object::{TaskData, TaskObject};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
+use tracing::error;
+
+/// The clipboard format that keeps every field of the tasks, as JSON.
+const TASKS_MIME_TYPE: &str = "application/x-mytool-tasks+json";
+
+/// The tasks as a Markdown checklist, one quick add text per line.
+fn checklist(tasks: &[TaskObject]) -> String {
+    tasks
+        .iter()
+        .map(|task_object| {
+            let check = if task_object.is_completed() { 'x' } else { ' ' };
+            format!("- [{check}] {}\n", quick_add::task_text(task_object))
+        })
+        .collect()
+}
+
+/// Splits a line of pasted text into whether it is checked and its text, without
+/// the list or checklist marker.
+fn parse_line(line: &str) -> (bool, &str) {
+    let line = line.trim();
+    let line = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .unwrap_or(line)
+        .trim_start();
+    for (marker, completed) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+        if let Some(text) = line.strip_prefix(marker) {
+            return (completed, text.trim());
+        }
+    }
+    (false, line)
+}
+
+impl RnTodo {
+    /// The selected tasks, or the task that is shown in the detail pane.
+    fn clipboard_tasks(&self) -> Vec<TaskObject> {
+        let tasks = self.selected_tasks();
+        if !tasks.is_empty() {
+            return tasks;
+        }
+        self.imp().task_detail.task().into_iter().collect()
+    }
+
+    /// Copies the selected tasks, returns whether there were any.
+    pub(crate) fn copy_selected_tasks(&self) -> bool {
+        let tasks = self.clipboard_tasks();
+        if tasks.is_empty() {
+            return false;
+        }
+        let task_data: Vec<TaskData> =
+            tasks.iter().map(TaskObject::task_data).collect();
+        let json = match serde_json::to_vec(&task_data) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("Serializing the copied tasks failed, Err: {e:?}");
+                return false;
+            }
+        };
+        let text = checklist(&tasks);
+        let provider = gdk::ContentProvider::new_union(&[
+            gdk::ContentProvider::for_bytes(
+                TASKS_MIME_TYPE,
+                &glib::Bytes::from_owned(json),
+            ),
+            gdk::ContentProvider::for_bytes(
+                "text/markdown",
+                &glib::Bytes::from(text.as_bytes()),
+            ),
+            gdk::ContentProvider::for_value(&text.to_value()),
+        ]);
+        if let Err(e) = self.clipboard().set_content(Some(&provider)) {
+            error!("Copying the tasks to the clipboard failed, Err: {e:?}");
+            return false;
+        }
+        true
+    }
+
+    /// Copies the selected tasks and removes them.
+    pub(crate) fn cut_selected_tasks(&self) {
+        let tasks = self.clipboard_tasks();
+        if !self.copy_selected_tasks() {
+            return;
+        }
+        for task_object in &tasks {
+            if let Some(collection) = self.collection_of(task_object) {
+                collection.remove_task(task_object);
+            }
+            if self.imp().task_detail.task().as_ref() == Some(task_object) {
+                self.close_task_detail();
+            }
+        }
+        self.show_undo_toast(&if tasks.len() == 1 {
+            String::from("Task cut")
+        } else {
+            format!("{} tasks cut", tasks.len())
+        });
+    }
+
+    /// Adds the tasks of the clipboard to the current collection, text is added as one
+    /// task per line, to the collection that the line names.
+    pub(crate) async fn paste_tasks(&self) {
+        let Some(collection) = self.imp().current_collection.borrow().clone() else {
+            return;
+        };
+        let clipboard = self.clipboard();
+        let tasks = if clipboard.formats().contain_mime_type(TASKS_MIME_TYPE) {
+            self.read_clipboard_tasks(&clipboard)
+                .await
+                .into_iter()
+                .map(|task_object| (task_object, None))
+                .collect()
+        } else {
+            match clipboard.read_text_future().await {
+                Ok(Some(text)) => self.tasks_from_text(&text),
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    error!("Reading text from the clipboard failed, Err: {e:?}");
+                    Vec::new()
+                }
+            }
+        };
+        for (task_object, target) in &tasks {
+            self.add_task(task_object, Some(target.as_ref().unwrap_or(&collection)));
+        }
+    }
+
+    async fn read_clipboard_tasks(
+        &self,
+        clipboard: &gdk::Clipboard,
+    ) -> Vec<TaskObject> {
+        let stream = match clipboard
+            .read_future(&[TASKS_MIME_TYPE], glib::Priority::DEFAULT)
+            .await
+        {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                error!("Reading tasks from the clipboard failed, Err: {e:?}");
+                return Vec::new();
+            }
+        };
+        let output = gio::MemoryOutputStream::new_resizable();
+        if let Err(e) = output
+            .splice_future(
+                &stream,
+                gio::OutputStreamSpliceFlags::CLOSE_SOURCE
+                    | gio::OutputStreamSpliceFlags::CLOSE_TARGET,
+                glib::Priority::DEFAULT,
+            )
+            .await
+        {
+            error!("Reading tasks from the clipboard failed, Err: {e:?}");
+            return Vec::new();
+        }
+        match serde_json::from_slice::<Vec<TaskData>>(&output.steal_as_bytes()) {
+            Ok(task_data) => task_data
+                .into_iter()
+                .map(TaskObject::from_task_data)
+                .collect(),
+            Err(e) => {
+                error!("Deserializing the pasted tasks failed, Err: {e:?}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// One task for every line that isn't empty, parsed like quick add text, with the
+    /// collection that the line names.
+    fn tasks_from_text(
+        &self,
+        text: &str,
+    ) -> Vec<(TaskObject, Option<CollectionObject>)> {
+        text.lines()
+            .map(parse_line)
+            .filter_map(|(completed, line)| {
+                let quick_add = self.parse_quick_add(line);
+                if quick_add.content.is_empty() {
+                    return None;
+                }
+                let task_object = quick_add.to_task();
+                task_object.set_completed(completed);
+                let target = quick_add
+                    .collection
+                    .and_then(|title| self.collection_by_title(&title));
+                Some((task_object, target))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,391 @@
+// Imports
+use super::RnTodo;
+use crate::collection_object::{CollectionData, CollectionObject};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{AlertDialog, ResponseAppearance};
+use gtk::{gdk, gio, glib, glib::clone, Entry, ListBoxRow};
+
+impl RnTodo {
+    /// The `collection-row` actions of a row in the collections list, they act on its
+    /// collection.
+    fn collection_actions(
+        &self,
+        collection: &CollectionObject,
+    ) -> gio::SimpleActionGroup {
+        let actions = gio::SimpleActionGroup::new();
+
+        let action_rename = gio::SimpleAction::new("rename", None);
+        action_rename.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    #[weak]
+                    collection,
+                    async move {
+                        todo.rename_collection(&collection).await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_rename);
+
+        let action_duplicate = gio::SimpleAction::new("duplicate", None);
+        action_duplicate.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection,
+            move |_, _| {
+                todo.duplicate_collection(&collection);
+            }
+        ));
+        actions.add_action(&action_duplicate);
+
+        let action_save_template = gio::SimpleAction::new("save-template", None);
+        action_save_template.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    #[weak]
+                    collection,
+                    async move {
+                        todo.save_as_template(&collection).await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_save_template);
+
+        let action_merge_into =
+            gio::SimpleAction::new("merge-into", Some(&u32::static_variant_type()));
+        action_merge_into.set_enabled(!collection.is_inbox());
+        action_merge_into.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection,
+            move |_, parameter| {
+                let Some(into) = parameter
+                    .and_then(|parameter| parameter.get::<u32>())
+                    .and_then(|position| todo.collections().item(position))
+                    .and_downcast::<CollectionObject>()
+                else {
+                    return;
+                };
+                todo.merge_collection(&collection, &into);
+            }
+        ));
+        actions.add_action(&action_merge_into);
+
+        let action_delete = gio::SimpleAction::new("delete", None);
+        action_delete.set_enabled(!collection.is_inbox());
+        action_delete.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    #[weak]
+                    collection,
+                    async move {
+                        todo.delete_collection(&collection).await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_delete);
+
+        actions
+    }
+
+    /// The menu of the `collection-row` actions.
+    fn collection_menu(&self, collection: &CollectionObject) -> gio::Menu {
+        let menu = gio::Menu::new();
+
+        let section = gio::Menu::new();
+        section.append(Some("_Rename…"), Some("collection-row.rename"));
+        section.append(Some("D_uplicate"), Some("collection-row.duplicate"));
+        section.append(
+            Some("Save as _Template…"),
+            Some("collection-row.save-template"),
+        );
+        if !collection.is_inbox() {
+            let merge_menu = gio::Menu::new();
+            for (position, into) in self
+                .collections()
+                .iter::<CollectionObject>()
+                .filter_map(Result::ok)
+                .enumerate()
+            {
+                if &into == collection {
+                    continue;
+                }
+                let item = gio::MenuItem::new(Some(&into.title()), None);
+                item.set_action_and_target_value(
+                    Some("collection-row.merge-into"),
+                    Some(&(position as u32).to_variant()),
+                );
+                merge_menu.append_item(&item);
+            }
+            section.append_submenu(Some("_Merge into"), &merge_menu);
+        }
+        menu.append_section(None, &section);
+
+        if !collection.is_inbox() {
+            let section = gio::Menu::new();
+            section.append(Some("_Delete…"), Some("collection-row.delete"));
+            menu.append_section(None, &section);
+        }
+        menu
+    }
+
+    /// Adds the `collection-row` actions to the row, and opens their menu on right
+    /// click, long press and the menu key.
+    pub(super) fn add_collection_context_menu(
+        &self,
+        row: &ListBoxRow,
+        collection: &CollectionObject,
+    ) {
+        row.insert_action_group(
+            "collection-row",
+            Some(&self.collection_actions(collection)),
+        );
+
+        let click_gesture = gtk::GestureClick::builder()
+            .button(gdk::BUTTON_SECONDARY)
+            .build();
+        click_gesture.connect_pressed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            row,
+            #[weak]
+            collection,
+            move |gesture, _, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                todo.show_collection_menu(&row, &collection, Some((x, y)));
+            }
+        ));
+        row.add_controller(click_gesture);
+
+        let long_press_gesture =
+            gtk::GestureLongPress::builder().touch_only(true).build();
+        long_press_gesture.connect_pressed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            row,
+            #[weak]
+            collection,
+            move |gesture, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                todo.show_collection_menu(&row, &collection, Some((x, y)));
+            }
+        ));
+        row.add_controller(long_press_gesture);
+
+        let shortcuts = gtk::ShortcutController::new();
+        shortcuts.add_shortcut(gtk::Shortcut::new(
+            gtk::ShortcutTrigger::parse_string("Menu|<Shift>F10"),
+            Some(gtk::CallbackAction::new(clone!(
+                #[weak(rename_to = todo)]
+                self,
+                #[weak]
+                row,
+                #[weak]
+                collection,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, _| {
+                    todo.show_collection_menu(&row, &collection, None);
+                    glib::Propagation::Stop
+                }
+            ))),
+        ));
+        row.add_controller(shortcuts);
+    }
+
+    /// Pops up the menu at the position in the row, or below it.
+    fn show_collection_menu(
+        &self,
+        row: &ListBoxRow,
+        collection: &CollectionObject,
+        position: Option<(f64, f64)>,
+    ) {
+        let popover = gtk::PopoverMenu::builder()
+            .menu_model(&self.collection_menu(collection))
+            .has_arrow(position.is_none())
+            .build();
+        if let Some((x, y)) = position {
+            popover.set_halign(gtk::Align::Start);
+            popover
+                .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+        }
+        popover.set_parent(row);
+        // The menu is built again the next time, with the current collections
+        popover.connect_closed(|popover| {
+            glib::idle_add_local_once(clone!(
+                #[weak]
+                popover,
+                move || {
+                    popover.unparent();
+                }
+            ));
+        });
+        popover.popup();
+    }
+
+    async fn rename_collection(&self, collection: &CollectionObject) {
+        let entry = Entry::builder()
+            .placeholder_text("Name")
+            .text(collection.title())
+            .activates_default(true)
+            .build();
+
+        let cancel_response = "cancel";
+        let rename_response = "rename";
+
+        let dialog = AlertDialog::builder()
+            .heading("Rename Collection")
+            .close_response(cancel_response)
+            .default_response(rename_response)
+            .extra_child(&entry)
+            .build();
+        dialog
+            .add_responses(&[(cancel_response, "Cancel"), (rename_response, "Rename")]);
+        dialog.set_response_appearance(rename_response, ResponseAppearance::Suggested);
+
+        // The name can't be empty
+        entry.connect_changed(clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                let empty = entry.text().is_empty();
+                dialog.set_response_enabled(rename_response, !empty);
+                if empty {
+                    entry.add_css_class("error");
+                } else {
+                    entry.remove_css_class("error");
+                }
+            }
+        ));
+
+        if dialog.choose_future(self).await != rename_response {
+            return;
+        }
+        collection.set_title(entry.text().to_string());
+    }
+
+    /// Inserts a copy of the collection and its tasks after it.
+    fn duplicate_collection(&self, collection: &CollectionObject) {
+        let collection_data = collection.to_collection_data();
+        let duplicate = CollectionObject::from_collection_data(CollectionData {
+            title: format!("{} (Copy)", collection_data.title),
+            inbox: false,
+            ..collection_data
+        });
+
+        let collections = self.collections();
+        let position = collections
+            .find(collection)
+            .map_or(collections.n_items(), |i| i + 1);
+        collections.insert(position, &duplicate);
+        self.show_collection(&duplicate);
+        self.select_collection_row();
+    }
+
+    /// Moves all tasks to the end of `into`, with their sections, and removes the
+    /// emptied collection.
+    fn merge_collection(&self, collection: &CollectionObject, into: &CollectionObject) {
+        if collection == into || collection.is_inbox() {
+            return;
+        }
+        for section in collection.sections() {
+            into.add_section(&section);
+        }
+        let tasks: Vec<glib::Object> = collection
+            .tasks()
+            .iter::<glib::Object>()
+            .filter_map(Result::ok)
+            .collect();
+        collection.tasks().remove_all();
+        into.tasks().extend_from_slice(&tasks);
+
+        self.remove_collection(collection);
+        self.show_collection(into);
+        self.select_collection_row();
+
+        self.show_undo_toast(&format!(
+            "“{}” merged into “{}”",
+            collection.title(),
+            into.title()
+        ));
+    }
+
+    async fn delete_collection(&self, collection: &CollectionObject) {
+        if collection.is_inbox() {
+            return;
+        }
+        let cancel_response = "cancel";
+        let delete_response = "delete";
+
+        let n_tasks = collection.tasks().n_items();
+        let dialog = AlertDialog::builder()
+            .heading(format!("Delete “{}”?", collection.title()))
+            .body(match n_tasks {
+                0 => String::from("The collection is empty."),
+                1 => String::from("Its task is deleted as well."),
+                n_tasks => format!("Its {n_tasks} tasks are deleted as well."),
+            })
+            .close_response(cancel_response)
+            .default_response(cancel_response)
+            .build();
+        dialog
+            .add_responses(&[(cancel_response, "Cancel"), (delete_response, "Delete")]);
+        dialog
+            .set_response_appearance(delete_response, ResponseAppearance::Destructive);
+
+        if dialog.choose_future(self).await != delete_response {
+            return;
+        }
+        self.remove_collection(collection);
+        self.show_undo_toast(&format!("“{}” deleted", collection.title()));
+    }
+
+    /// Removes the collection, showing the Inbox instead if it was shown.
+    fn remove_collection(&self, collection: &CollectionObject) {
+        let collections = self.collections();
+        let Some(position) = collections.find(collection) else {
+            return;
+        };
+        if self.imp().current_collection.borrow().as_ref() == Some(collection) {
+            if let Some(inbox) = self.inbox() {
+                self.set_current_collection(inbox);
+            }
+        }
+        if self
+            .imp()
+            .task_detail
+            .task()
+            .is_some_and(|task_object| collection.tasks().find(&task_object).is_some())
+        {
+            self.close_task_detail();
+        }
+        collections.remove(position);
+        self.select_collection_row();
+    }
+}
@@ -0,0 +1,570 @@
+// Imports
+use super::task_menu::{due_menu, priority_menu};
+use crate::task_object::{TaskObject, TaskSubtask};
+use adw::{prelude::*, subclass::prelude::*, ActionRow, EntryRow};
+use chrono::{Days, Local, NaiveTime, TimeDelta, TimeZone};
+use gtk::{
+    gio, glib, glib::clone, Button, CheckButton, CompositeTemplate, ListBox,
+    MenuButton, TextView, Widget,
+};
+use std::cell::RefCell;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/task_detail.ui")]
+    pub(crate) struct RnTaskDetail {
+        pub(crate) task: RefCell<Option<TaskObject>>,
+        /// The bindings and notify handlers that connect the shown task to the pane
+        pub(crate) bindings: RefCell<Vec<glib::Binding>>,
+        pub(crate) handlers: RefCell<Vec<glib::SignalHandlerId>>,
+
+        #[template_child]
+        pub(crate) title_row: TemplateChild<EntryRow>,
+        #[template_child]
+        pub(crate) due_row: TemplateChild<ActionRow>,
+        #[template_child]
+        pub(crate) due_button: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) priority_row: TemplateChild<ActionRow>,
+        #[template_child]
+        pub(crate) priority_button: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) notes_view: TemplateChild<TextView>,
+        #[template_child]
+        pub(crate) labels_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) add_label_row: TemplateChild<EntryRow>,
+        #[template_child]
+        pub(crate) subtasks_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) add_subtask_row: TemplateChild<EntryRow>,
+        #[template_child]
+        pub(crate) attachments_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) add_attachment_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) reminders_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) add_reminder_button: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) created_row: TemplateChild<ActionRow>,
+        #[template_child]
+        pub(crate) modified_row: TemplateChild<ActionRow>,
+        #[template_child]
+        pub(crate) completed_row: TemplateChild<ActionRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnTaskDetail {
+        const NAME: &'static str = "RnTaskDetail";
+        type Type = super::RnTaskDetail;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnTaskDetail {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup_callbacks();
+        }
+
+        fn dispose(&self) {
+            self.obj().set_task(None);
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnTaskDetail {}
+}
+
+glib::wrapper! {
+    /// Shows and edits every property of a task.
+    ///
+    /// Expects the `task` actions of the shown task on an ancestor or on itself.
+    pub(crate) struct RnTaskDetail(ObjectSubclass<imp::RnTaskDetail>)
+        @extends Widget;
+}
+
+impl Default for RnTaskDetail {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnTaskDetail {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn task(&self) -> Option<TaskObject> {
+        self.imp().task.borrow().clone()
+    }
+
+    /// Shows the task, or nothing.
+    pub(crate) fn set_task(&self, task_object: Option<&TaskObject>) {
+        let imp = self.imp();
+        for binding in imp.bindings.take() {
+            binding.unbind();
+        }
+        if let Some(previous) = imp.task.replace(task_object.cloned()) {
+            for handler in imp.handlers.take() {
+                previous.disconnect(handler);
+            }
+        }
+        let Some(task_object) = task_object else {
+            return;
+        };
+
+        let bindings = vec![
+            // An empty title is not applied, so the task keeps its last one
+            task_object
+                .bind_property("content", &imp.title_row.get(), "text")
+                .bidirectional()
+                .sync_create()
+                .transform_from(|_, text: String| {
+                    (!text.trim().is_empty()).then_some(text)
+                })
+                .build(),
+            task_object
+                .bind_property("notes", &imp.notes_view.buffer(), "text")
+                .bidirectional()
+                .sync_create()
+                .build(),
+        ];
+        imp.bindings.replace(bindings);
+
+        let mut handlers = Vec::new();
+        for (property, update) in [
+            ("content", Self::update_labels as fn(&Self)),
+            ("due", Self::update_due),
+            ("recurrence", Self::update_due),
+            ("priority", Self::update_priority),
+            ("subtasks", Self::update_subtasks),
+            ("attachments", Self::update_attachments),
+            ("reminders", Self::update_reminders),
+        ] {
+            handlers.push(task_object.connect_notify_local(
+                Some(property),
+                clone!(
+                    #[weak(rename_to = detail)]
+                    self,
+                    move |_, _| update(&detail)
+                ),
+            ));
+        }
+        handlers.push(task_object.connect_notify_local(
+            None,
+            clone!(
+                #[weak(rename_to = detail)]
+                self,
+                move |_, _| detail.update_history()
+            ),
+        ));
+        imp.handlers.replace(handlers);
+
+        self.update_labels();
+        self.update_due();
+        self.update_priority();
+        self.update_subtasks();
+        self.update_attachments();
+        self.update_reminders();
+        self.update_history();
+    }
+
+    fn setup_callbacks(&self) {
+        let imp = self.imp();
+
+        // The dates of the menu depend on the day it is opened
+        imp.due_button.set_create_popup_func(|due_button| {
+            due_button.set_menu_model(Some(&due_menu("task")));
+        });
+        imp.priority_button
+            .set_menu_model(Some(&priority_menu("task")));
+        imp.add_reminder_button.set_create_popup_func(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            move |add_reminder_button| {
+                add_reminder_button.set_menu_model(Some(&detail.reminder_menu()));
+            }
+        ));
+
+        imp.add_label_row.connect_entry_activated(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            move |add_label_row| {
+                let Some(task_object) = detail.task() else {
+                    return;
+                };
+                for word in add_label_row.text().split_whitespace() {
+                    let label = word.trim_start_matches(['#', '@']);
+                    if !label.is_empty() {
+                        task_object.add_label(label);
+                    }
+                }
+                add_label_row.set_text("");
+            }
+        ));
+        imp.add_subtask_row.connect_entry_activated(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            move |add_subtask_row| {
+                let title = add_subtask_row.text().trim().to_string();
+                if title.is_empty() {
+                    return;
+                }
+                detail.change_subtasks(|subtasks| {
+                    subtasks.push(TaskSubtask {
+                        title,
+                        completed: false,
+                    })
+                });
+                add_subtask_row.set_text("");
+            }
+        ));
+        imp.add_attachment_button.connect_clicked(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    detail,
+                    async move {
+                        detail.add_attachment().await;
+                    }
+                ));
+            }
+        ));
+
+        let actions = gio::SimpleActionGroup::new();
+        // The time of the reminder as a unix timestamp
+        let action_add_reminder =
+            gio::SimpleAction::new("add-reminder", Some(glib::VariantTy::INT64));
+        action_add_reminder.connect_activate(clone!(
+            #[weak(rename_to = detail)]
+            self,
+            move |_, parameter| {
+                let Some(time) = parameter
+                    .and_then(|parameter| parameter.get::<i64>())
+                    .and_then(|timestamp| Local.timestamp_opt(timestamp, 0).single())
+                else {
+                    return;
+                };
+                let Some(task_object) = detail.task() else {
+                    return;
+                };
+                let mut reminders = task_object.reminders();
+                if !reminders.0.contains(&time) {
+                    reminders.0.push(time);
+                    reminders.0.sort();
+                    task_object.set_reminders(reminders);
+                }
+            }
+        ));
+        actions.add_action(&action_add_reminder);
+        self.insert_action_group("detail", Some(&actions));
+    }
+
+    fn update_due(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let mut subtitle = match task_object.due() {
+            Some(due) => {
+                let mut date = due.date.format("%a, %b %-d, %Y").to_string();
+                if let Some(time) = due.time {
+                    date += &format!(" · {}", time.format("%H:%M"));
+                }
+                date
+            }
+            None => String::from("No Date"),
+        };
+        if let Some(recurrence) = task_object.recurrence() {
+            subtitle += &format!(" · {}", recurrence.description());
+        }
+        self.imp().due_row.set_subtitle(&subtitle);
+    }
+
+    fn update_priority(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let subtitle = match task_object.priority() {
+            0 => String::from("None"),
+            priority => format!("Priority {priority}"),
+        };
+        self.imp().priority_row.set_subtitle(&subtitle);
+    }
+
+    fn update_labels(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let imp = self.imp();
+        clear_list(&imp.labels_list, &imp.add_label_row.get());
+        for (position, label) in task_object.labels().into_iter().enumerate() {
+            let row = ActionRow::builder()
+                .title(format!("#{label}"))
+                .use_markup(false)
+                .build();
+            row.add_suffix(&remove_button(self, "Remove Label", move |detail| {
+                if let Some(task_object) = detail.task() {
+                    task_object.remove_label(&label);
+                }
+            }));
+            imp.labels_list.insert(&row, position as i32);
+        }
+    }
+
+    fn update_subtasks(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let imp = self.imp();
+        clear_list(&imp.subtasks_list, &imp.add_subtask_row.get());
+        for (position, subtask) in task_object.subtasks().0.into_iter().enumerate() {
+            let check_button = CheckButton::builder()
+                .active(subtask.completed)
+                .valign(gtk::Align::Center)
+                .build();
+            check_button.connect_toggled(clone!(
+                #[weak(rename_to = detail)]
+                self,
+                move |check_button| {
+                    let completed = check_button.is_active();
+                    detail.change_subtasks(|subtasks| {
+                        if let Some(subtask) = subtasks.get_mut(position) {
+                            subtask.completed = completed;
+                        }
+                    });
+                }
+            ));
+            let row = ActionRow::builder()
+                .title(subtask.title)
+                .use_markup(false)
+                .activatable_widget(&check_button)
+                .build();
+            row.add_prefix(&check_button);
+            row.add_suffix(&remove_button(self, "Remove Subtask", move |detail| {
+                detail.change_subtasks(|subtasks| {
+                    if position < subtasks.len() {
+                        subtasks.remove(position);
+                    }
+                });
+            }));
+            imp.subtasks_list.insert(&row, position as i32);
+        }
+    }
+
+    fn update_attachments(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let imp = self.imp();
+        imp.attachments_list.remove_all();
+        for uri in task_object.attachments() {
+            let file = gio::File::for_uri(&uri);
+            let title = file
+                .basename()
+                .map(|basename| basename.display().to_string())
+                .unwrap_or_else(|| uri.clone());
+            let subtitle = file
+                .parent()
+                .and_then(|parent| parent.path())
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            let row = ActionRow::builder()
+                .title(title)
+                .subtitle(subtitle)
+                .use_markup(false)
+                .activatable(true)
+                .build();
+            row.connect_activated(clone!(
+                #[weak(rename_to = detail)]
+                self,
+                #[strong]
+                file,
+                move |_| {
+                    gtk::FileLauncher::new(Some(&file)).launch(
+                        detail.root().and_downcast_ref::<gtk::Window>(),
+                        gio::Cancellable::NONE,
+                        |res| {
+                            if let Err(e) = res {
+                                error!("Opening attachment failed, Err: {e:?}");
+                            }
+                        },
+                    );
+                }
+            ));
+            row.add_suffix(&remove_button(self, "Remove Attachment", move |detail| {
+                if let Some(task_object) = detail.task() {
+                    let mut attachments = task_object.attachments();
+                    attachments.retain(|attachment| *attachment != uri);
+                    task_object.set_attachments(attachments);
+                }
+            }));
+            imp.attachments_list.append(&row);
+        }
+    }
+
+    fn update_reminders(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let imp = self.imp();
+        imp.reminders_list.remove_all();
+        let now = Local::now();
+        for time in task_object.reminders().0 {
+            let row = ActionRow::builder()
+                .title(time.format("%a, %b %-d, %Y · %H:%M").to_string())
+                .build();
+            if time <= now {
+                row.add_css_class("dim-label");
+            }
+            row.add_suffix(&remove_button(self, "Remove Reminder", move |detail| {
+                if let Some(task_object) = detail.task() {
+                    let mut reminders = task_object.reminders();
+                    reminders.0.retain(|reminder| *reminder != time);
+                    task_object.set_reminders(reminders);
+                }
+            }));
+            imp.reminders_list.append(&row);
+        }
+    }
+
+    fn update_history(&self) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let imp = self.imp();
+        for (row, time) in [
+            (&imp.created_row, task_object.created_at()),
+            (&imp.modified_row, task_object.modified_at()),
+            (&imp.completed_row, task_object.completed_at()),
+        ] {
+            row.set_visible(time.is_some());
+            if let Some(time) = time {
+                row.set_subtitle(&time.format("%b %-d, %Y · %H:%M").to_string());
+            }
+        }
+    }
+
+    fn change_subtasks(&self, change: impl FnOnce(&mut Vec<TaskSubtask>)) {
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let mut subtasks = task_object.subtasks();
+        change(&mut subtasks.0);
+        task_object.set_subtasks(subtasks);
+    }
+
+    /// In an hour, tomorrow morning and at the due time, if it is still ahead.
+    fn reminder_menu(&self) -> gio::Menu {
+        let now = Local::now();
+        let morning = NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default();
+        let mut times = vec![
+            ("In an _Hour", Some(now + TimeDelta::hours(1))),
+            (
+                "Tomorrow _Morning",
+                (now.date_naive() + Days::new(1))
+                    .and_time(morning)
+                    .and_local_timezone(Local)
+                    .earliest(),
+            ),
+        ];
+        if let Some(due) = self.task().and_then(|task_object| task_object.due()) {
+            let due_time = due
+                .date
+                .and_time(due.time.unwrap_or(morning))
+                .and_local_timezone(Local)
+                .earliest()
+                .filter(|due_time| *due_time > now);
+            times.push(("At the _Due Time", due_time));
+        }
+
+        let menu = gio::Menu::new();
+        for (label, time) in times {
+            let Some(time) = time else {
+                continue;
+            };
+            let item = gio::MenuItem::new(Some(label), None);
+            item.set_action_and_target_value(
+                Some("detail.add-reminder"),
+                Some(&time.timestamp().to_variant()),
+            );
+            menu.append_item(&item);
+        }
+        menu
+    }
+
+    async fn add_attachment(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Attach File")
+            .modal(true)
+            .build();
+        let file = match dialog
+            .open_future(self.root().and_downcast_ref::<gtk::Window>())
+            .await
+        {
+            Ok(file) => file,
+            Err(e) => {
+                if !e.matches(gtk::DialogError::Dismissed) {
+                    error!("Choosing attachment failed, Err: {e:?}");
+                }
+                return;
+            }
+        };
+        let Some(task_object) = self.task() else {
+            return;
+        };
+        let uri = file.uri().to_string();
+        let mut attachments = task_object.attachments();
+        if !attachments.contains(&uri) {
+            attachments.push(uri);
+            task_object.set_attachments(attachments);
+        }
+    }
+}
+
+/// Removes all rows of the list except `keep`.
+fn clear_list(list: &ListBox, keep: &impl IsA<Widget>) {
+    let mut child = list.first_child();
+    while let Some(row) = child {
+        child = row.next_sibling();
+        if row != *keep.upcast_ref::<Widget>() {
+            list.remove(&row);
+        }
+    }
+}
+
+/// A button that calls `remove` with the detail pane.
+fn remove_button(
+    detail: &RnTaskDetail,
+    tooltip: &str,
+    remove: impl Fn(&RnTaskDetail) + 'static,
+) -> Button {
+    let button = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text(tooltip)
+        .valign(gtk::Align::Center)
+        .build();
+    button.add_css_class("flat");
+    button.connect_clicked(clone!(
+        #[weak]
+        detail,
+        move |_| remove(&detail)
+    ));
+    button
+}
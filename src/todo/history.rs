@@ -0,0 +1,426 @@
+// Imports
+use super::RnTodo;
+use crate::collection_object::CollectionObject;
+use crate::RnAppWindow;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gio, glib, glib::clone};
+use std::collections::HashMap;
+
+/// Changes of the same property within this time are undone together, in µs.
+const MERGE_INTERVAL: i64 = 1_000_000;
+/// The number of steps that can be undone, older ones are forgotten.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// A reversible change of the tasks or collections.
+#[derive(Debug, Clone)]
+enum Change {
+    /// A property of a task or collection
+    Property {
+        object: glib::Object,
+        property: String,
+        old: glib::Value,
+        new: glib::Value,
+    },
+    /// The items of the collections, or of the tasks of a collection
+    Items {
+        store: gio::ListStore,
+        old: Vec<glib::Object>,
+        new: Vec<glib::Object>,
+    },
+}
+
+impl Change {
+    fn apply(&self, undo: bool) {
+        match self {
+            Self::Property {
+                object,
+                property,
+                old,
+                new,
+            } => object.set_property_from_value(property, if undo { old } else { new }),
+            Self::Items { store, old, new } => {
+                store.splice(0, store.n_items(), if undo { old } else { new })
+            }
+        }
+    }
+
+    /// Whether both change the same property of the same object.
+    fn same_property(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Property {
+                    object, property, ..
+                },
+                Self::Property {
+                    object: other_object,
+                    property: other_property,
+                    ..
+                },
+            ) => object == other_object && property == other_property,
+            _ => false,
+        }
+    }
+}
+
+/// The changes made in one iteration of the main loop, undone together.
+#[derive(Debug)]
+struct Step {
+    changes: Vec<Change>,
+    /// The monotonic time of the last change
+    time: i64,
+}
+
+/// The changes that can be undone and redone, recorded from the signals of the tasks
+/// and collections.
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    pending: Vec<Change>,
+    commit_queued: bool,
+    /// Set while changes are undone or redone, so that they aren't recorded again
+    replaying: bool,
+    /// The last values of the properties, to know what they changed from
+    values: HashMap<glib::Object, HashMap<String, glib::Value>>,
+    /// The last items of the stores, to know what they changed from
+    items: HashMap<gio::ListStore, Vec<glib::Object>>,
+    /// The notify handlers of the watched tasks and collections
+    watched: HashMap<glib::Object, glib::SignalHandlerId>,
+    /// The items-changed handlers of the watched stores
+    watched_stores: HashMap<gio::ListStore, glib::SignalHandlerId>,
+}
+
+impl RnTodo {
+    /// Records the changes of the collections, and of everything they contain.
+    pub(super) fn setup_history(&self) {
+        self.watch_store(&self.collections());
+    }
+
+    /// Forgets all changes, when different data is loaded.
+    pub(super) fn clear_history(&self) {
+        {
+            let mut history = self.imp().history.borrow_mut();
+            history.undo.clear();
+            history.redo.clear();
+            history.pending.clear();
+            history.values.clear();
+            history.items.clear();
+            for (object, handler_id) in history.watched.drain() {
+                object.disconnect(handler_id);
+            }
+            for (store, handler_id) in history.watched_stores.drain() {
+                store.disconnect(handler_id);
+            }
+        }
+        // The collections that are loaded now are recorded from here on
+        self.setup_history();
+        self.update_history_actions();
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        let history = self.imp().history.borrow();
+        !history.undo.is_empty() || !history.pending.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.imp().history.borrow().redo.is_empty()
+    }
+
+    pub(crate) fn undo(&self) {
+        self.commit_history_step();
+        let Some(step) = self.imp().history.borrow_mut().undo.pop() else {
+            return;
+        };
+        self.replay(&step, true);
+        self.imp().history.borrow_mut().redo.push(step);
+        self.update_history_actions();
+    }
+
+    pub(crate) fn redo(&self) {
+        self.commit_history_step();
+        let Some(step) = self.imp().history.borrow_mut().redo.pop() else {
+            return;
+        };
+        self.replay(&step, false);
+        self.imp().history.borrow_mut().undo.push(step);
+        self.update_history_actions();
+    }
+
+    /// Shows a toast without a button.
+    pub(crate) fn show_toast(&self, title: &str) {
+        let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        let toast = adw::Toast::builder().title(title).use_markup(false).build();
+        appwindow.toast_overlay().add_toast(toast);
+    }
+
+    /// Shows a toast that undoes the last change, after something was removed.
+    pub(crate) fn show_undo_toast(&self, title: &str) {
+        let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        let toast = adw::Toast::builder()
+            .title(title)
+            .button_label("_Undo")
+            .action_name("win.undo")
+            .use_markup(false)
+            .build();
+        appwindow.toast_overlay().add_toast(toast);
+    }
+
+    fn replay(&self, step: &Step, undo: bool) {
+        self.imp().history.borrow_mut().replaying = true;
+        if undo {
+            step.changes
+                .iter()
+                .rev()
+                .for_each(|change| change.apply(true));
+        } else {
+            step.changes.iter().for_each(|change| change.apply(false));
+        }
+        self.imp().history.borrow_mut().replaying = false;
+
+        // The shown collection or task may not exist anymore
+        let collections = self.collections();
+        let current_collection = self.imp().current_collection.borrow().clone();
+        if current_collection
+            .is_some_and(|collection| collections.find(&collection).is_none())
+        {
+            if let Some(inbox) = self.inbox() {
+                self.set_current_collection(inbox);
+            }
+        }
+        if self
+            .imp()
+            .task_detail
+            .task()
+            .is_some_and(|task_object| self.collection_of(&task_object).is_none())
+        {
+            self.close_task_detail();
+        }
+    }
+
+    fn watch_store(&self, store: &gio::ListStore) {
+        let items: Vec<glib::Object> = store
+            .iter::<glib::Object>()
+            .filter_map(Result::ok)
+            .collect();
+        for item in &items {
+            self.watch_object(item);
+        }
+        let handler_id = store.connect_items_changed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |store, _, _, _| {
+                todo.record_items(store);
+            }
+        ));
+        let mut history = self.imp().history.borrow_mut();
+        history.items.insert(store.clone(), items);
+        history.watched_stores.insert(store.clone(), handler_id);
+    }
+
+    /// Stops recording the changes of an object that left its store, an undo that
+    /// brings it back watches it again.
+    fn unwatch_object(&self, object: &glib::Object) {
+        let store = {
+            let mut history = self.imp().history.borrow_mut();
+            let Some(handler_id) = history.watched.remove(object) else {
+                return;
+            };
+            object.disconnect(handler_id);
+            history.values.remove(object);
+
+            let store = object
+                .downcast_ref::<CollectionObject>()
+                .map(CollectionObject::tasks);
+            if let Some(store) = &store {
+                if let Some(handler_id) = history.watched_stores.remove(store) {
+                    store.disconnect(handler_id);
+                }
+                history.items.remove(store);
+            }
+            store
+        };
+        if let Some(store) = store {
+            for item in store.iter::<glib::Object>().filter_map(Result::ok) {
+                self.unwatch_object(&item);
+            }
+        }
+    }
+
+    /// Records the changes of the properties of a task or collection, and of the tasks
+    /// of a collection.
+    fn watch_object(&self, object: &glib::Object) {
+        if self.imp().history.borrow().watched.contains_key(object) {
+            return;
+        }
+        let values = object
+            .list_properties()
+            .iter()
+            .filter(|pspec| {
+                pspec.flags().contains(glib::ParamFlags::READWRITE)
+                    && !pspec.value_type().is_a(glib::Object::static_type())
+            })
+            .map(|pspec| {
+                (
+                    pspec.name().to_string(),
+                    object.property_value(pspec.name()),
+                )
+            })
+            .collect();
+        let handler_id = object.connect_notify_local(
+            None,
+            clone!(
+                #[weak(rename_to = todo)]
+                self,
+                move |object, pspec| {
+                    todo.record_property(object, pspec.name());
+                }
+            ),
+        );
+        {
+            let mut history = self.imp().history.borrow_mut();
+            history.values.insert(object.clone(), values);
+            history.watched.insert(object.clone(), handler_id);
+        }
+        if let Some(collection) = object.downcast_ref::<CollectionObject>() {
+            self.watch_store(&collection.tasks());
+        }
+    }
+
+    fn record_property(&self, object: &glib::Object, property: &str) {
+        let new = object.property_value(property);
+        let old = {
+            let mut history = self.imp().history.borrow_mut();
+            let Some(values) = history.values.get_mut(object) else {
+                return;
+            };
+            let Some(old) = values.insert(property.to_string(), new.clone()) else {
+                return;
+            };
+            if history.replaying {
+                return;
+            }
+            old
+        };
+        self.push_change(Change::Property {
+            object: object.clone(),
+            property: property.to_string(),
+            old,
+            new,
+        });
+    }
+
+    fn record_items(&self, store: &gio::ListStore) {
+        let new: Vec<glib::Object> = store
+            .iter::<glib::Object>()
+            .filter_map(Result::ok)
+            .collect();
+        let (old, replaying) = {
+            let mut history = self.imp().history.borrow_mut();
+            let old = history
+                .items
+                .insert(store.clone(), new.clone())
+                .unwrap_or_default();
+            (old, history.replaying)
+        };
+        // Tasks that moved to another collection are still watched
+        let left: Vec<&glib::Object> = {
+            let history = self.imp().history.borrow();
+            old.iter()
+                .filter(|item| {
+                    !history.items.values().any(|items| items.contains(*item))
+                })
+                .collect()
+        };
+        for item in left {
+            self.unwatch_object(item);
+        }
+        for item in &new {
+            self.watch_object(item);
+        }
+        if replaying {
+            return;
+        }
+        self.push_change(Change::Items {
+            store: store.clone(),
+            old,
+            new,
+        });
+    }
+
+    /// Adds the change to the step that is committed once the main loop is idle.
+    fn push_change(&self, change: Change) {
+        let mut history = self.imp().history.borrow_mut();
+        history.pending.push(change);
+        if history.commit_queued {
+            return;
+        }
+        history.commit_queued = true;
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move || {
+                todo.commit_history_step();
+            }
+        ));
+    }
+
+    fn commit_history_step(&self) {
+        {
+            let mut history = self.imp().history.borrow_mut();
+            history.commit_queued = false;
+            let changes = std::mem::take(&mut history.pending);
+            if changes.is_empty() {
+                return;
+            }
+            let time = glib::monotonic_time();
+
+            // Typing into the title or notes of a task is undone at once
+            let merge = history.undo.last().is_some_and(|last| {
+                time - last.time < MERGE_INTERVAL
+                    && matches!(
+                        (last.changes.as_slice(), changes.as_slice()),
+                        ([last_change], [change]) if last_change.same_property(change)
+                    )
+            });
+            match history.undo.last_mut() {
+                Some(last) if merge => {
+                    if let (
+                        [Change::Property { new, .. }],
+                        [Change::Property { new: merged, .. }],
+                    ) = (last.changes.as_mut_slice(), changes.as_slice())
+                    {
+                        *new = merged.clone();
+                    }
+                    last.time = time;
+                }
+                _ => {
+                    history.undo.push(Step { changes, time });
+                    if history.undo.len() > MAX_UNDO_STEPS {
+                        history.undo.remove(0);
+                    }
+                }
+            }
+            history.redo.clear();
+        }
+        self.update_history_actions();
+    }
+
+    fn update_history_actions(&self) {
+        let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() else {
+            return;
+        };
+        for (name, enabled) in [("undo", self.can_undo()), ("redo", self.can_redo())] {
+            if let Some(action) = appwindow
+                .lookup_action(name)
+                .and_downcast::<gio::SimpleAction>()
+            {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+}
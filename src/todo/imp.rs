@@ -1,15 +1,21 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use adw::subclass::prelude::*;
-use adw::NavigationSplitView;
-use glib::subclass::InitializingObject;
+use adw::{BottomSheet, Breakpoint, NavigationSplitView, OverlaySplitView};
+use chrono::{DateTime, Local};
+use glib::subclass::{InitializingObject, Signal};
 use gtk::glib::SignalHandlerId;
 use gtk::{
-    gio, glib, CompositeTemplate, Entry, FilterListModel, ListBox, Stack, Widget,
+    gio, glib, ActionBar, CompositeTemplate, Entry, FilterListModel, Label, ListBox,
+    MenuButton, MultiSelection, SortListModel, Stack, Widget,
 };
+use once_cell::sync::Lazy;
 use std::cell::OnceCell;
 
+use super::{RnTaskBoard, RnTaskDetail};
 use crate::collection_object::CollectionObject;
+use crate::crypto::DataKey;
+use crate::template::TemplateData;
 
 // ANCHOR: struct
 // Object holding the state
@@ -19,18 +25,57 @@ pub struct RnTodo {
     #[template_child]
     pub entry: TemplateChild<Entry>,
     #[template_child]
+    pub quick_add_preview: TemplateChild<gtk::Box>,
+    #[template_child]
     pub tasks_list: TemplateChild<ListBox>,
     // 👇 all members below are new
     #[template_child]
     pub collections_list: TemplateChild<ListBox>,
     #[template_child]
+    pub templates_button: TemplateChild<MenuButton>,
+    #[template_child]
     pub split_view: TemplateChild<NavigationSplitView>,
     #[template_child]
     pub stack: TemplateChild<Stack>,
+    #[template_child]
+    pub layout_stack: TemplateChild<Stack>,
+    #[template_child]
+    pub board: TemplateChild<RnTaskBoard>,
+    #[template_child]
+    pub selection_bar: TemplateChild<ActionBar>,
+    #[template_child]
+    pub selection_label: TemplateChild<Label>,
+    #[template_child]
+    pub selection_menu_button: TemplateChild<MenuButton>,
+    #[template_child]
+    pub detail_breakpoint: TemplateChild<Breakpoint>,
+    #[template_child]
+    pub detail_sheet: TemplateChild<BottomSheet>,
+    #[template_child]
+    pub detail_split_view: TemplateChild<OverlaySplitView>,
+    #[template_child]
+    pub task_detail: TemplateChild<RnTaskDetail>,
     pub collections: OnceCell<gio::ListStore>,
     pub current_collection: RefCell<Option<CollectionObject>>,
     pub current_filter_model: RefCell<Option<FilterListModel>>,
+    pub current_sort_model: RefCell<Option<SortListModel>>,
+    /// The tasks that are selected in the list, mirrored from its rows
+    pub current_selection_model: RefCell<Option<MultiSelection>>,
+    /// The current collection and the handler that refreshes its task list
+    pub collection_changed_handler:
+        RefCell<Option<(CollectionObject, SignalHandlerId)>>,
+    /// The `collection` actions, holding the view options of the current collection
+    pub view_actions: OnceCell<gio::SimpleActionGroup>,
+    /// The `selection` actions, which change the selected tasks
+    pub selection_actions: OnceCell<gio::SimpleActionGroup>,
     pub tasks_changed_handler_id: RefCell<Option<SignalHandlerId>>,
+    pub data_key: RefCell<Option<DataKey>>,
+    pub locked: Cell<bool>,
+    pub tasks_changed_queued: Cell<bool>,
+    /// Reminders up to this time have been sent
+    pub reminders_checked_at: Cell<Option<DateTime<Local>>>,
+    pub history: RefCell<super::history::History>,
+    pub templates: RefCell<Vec<TemplateData>>,
 }
 // ANCHOR_END: struct
 
@@ -51,6 +96,44 @@ impl ObjectSubclass for RnTodo {
             window.remove_done_tasks();
         });
 
+        klass.install_action("todo.close-detail", None, |todo, _, _| {
+            todo.close_task_detail();
+        });
+
+        klass.install_action_async(
+            "todo.new-from-template",
+            Some(glib::VariantTy::UINT32),
+            |todo, _, parameter| async move {
+                if let Some(index) =
+                    parameter.and_then(|parameter| parameter.get::<u32>())
+                {
+                    todo.new_from_template(index as usize).await;
+                }
+            },
+        );
+        klass.install_action_async(
+            "todo.schedule-template",
+            Some(glib::VariantTy::UINT32),
+            |todo, _, parameter| async move {
+                if let Some(index) =
+                    parameter.and_then(|parameter| parameter.get::<u32>())
+                {
+                    todo.schedule_template(index as usize).await;
+                }
+            },
+        );
+        klass.install_action_async(
+            "todo.delete-template",
+            Some(glib::VariantTy::UINT32),
+            |todo, _, parameter| async move {
+                if let Some(index) =
+                    parameter.and_then(|parameter| parameter.get::<u32>())
+                {
+                    todo.delete_template(index as usize).await;
+                }
+            },
+        );
+
         // Create async action to create new collection and add to action group "win"
         klass.install_action_async(
             "win.new-collection",
@@ -66,6 +149,12 @@ impl ObjectSubclass for RnTodo {
     }
 }
 impl ObjectImpl for RnTodo {
+    fn signals() -> &'static [Signal] {
+        static SIGNALS: Lazy<Vec<Signal>> =
+            Lazy::new(|| vec![Signal::builder("tasks-changed").build()]);
+        SIGNALS.as_ref()
+    }
+
     fn constructed(&self) {
         // Call "constructed" on parent
         self.parent_constructed();
@@ -73,8 +162,13 @@ impl ObjectImpl for RnTodo {
         // Setup
         let obj = self.obj();
         obj.setup_collections();
-        obj.restore_data();
+        obj.setup_history();
         obj.setup_callbacks();
+        obj.setup_view_actions();
+        obj.setup_selection();
+        obj.setup_task_detail();
+        obj.setup_reminders();
+        obj.setup_template_schedules();
     }
 }
 
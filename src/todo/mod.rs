@@ -1,19 +1,40 @@
+mod board;
+mod clipboard;
+mod collection_menu;
+mod detail;
+mod history;
 mod imp;
+mod selection;
+mod task_menu;
+mod templates;
 
+use std::cmp::Ordering;
 use std::fs::File;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use adw::{ActionRow, AlertDialog, ResponseAppearance};
 use gtk::{
-    gio, glib, glib::clone, pango, Align, CheckButton, CustomFilter, Entry,
-    FilterListModel, Label, ListBoxRow, NoSelection,
+    gdk, gio, glib, glib::clone, pango, Align, CheckButton, CustomFilter, CustomSorter,
+    Entry, FilterListModel, Label, ListBoxRow, MultiSelection, SortListModel,
 };
 
-use crate::collection_object::{CollectionData, CollectionObject};
+pub(crate) use board::RnTaskBoard;
+pub(crate) use detail::RnTaskDetail;
+
+use crate::collection_object::{
+    BoardColumns, CollectionData, CollectionObject, TaskGroup, TaskLayout, TaskSort,
+    TodoData, ViewOptions,
+};
+use crate::crypto::{self, DataKey};
+use crate::quick_add::{DateLanguage, QuickAdd};
 use crate::task_object::TaskObject;
-use crate::utils::data_path;
-use crate::RnApp;
+use crate::utils::{backup_path, data_path, write_data_file};
+use crate::views::{add_task_drag_source, add_task_drop_target};
+use crate::RnAppWindow;
+use anyhow::Context;
+use chrono::{Datelike, Days, Local, NaiveDate};
+use tracing::error;
 
 glib::wrapper! {
     pub struct RnTodo(ObjectSubclass<imp::RnTodo>)
@@ -41,7 +62,7 @@ impl RnTodo {
             .expect("`current_collection` should be set in `set_current_collections`.")
     }
 
-    fn collections(&self) -> gio::ListStore {
+    pub(crate) fn collections(&self) -> gio::ListStore {
         self.imp()
             .collections
             .get()
@@ -49,47 +70,333 @@ impl RnTodo {
             .clone()
     }
 
-    pub fn set_filter(&self) {
-        self.imp()
+    /// All tasks of every collection, together with the collection they belong to.
+    pub(crate) fn all_tasks(&self) -> Vec<(CollectionObject, TaskObject)> {
+        self.collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .flat_map(|collection| {
+                collection
+                    .tasks()
+                    .iter::<TaskObject>()
+                    .filter_map(Result::ok)
+                    .map(move |task_object| (collection.clone(), task_object))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Emits `tasks-changed` once the main loop is idle, so that bulk changes only emit it once.
+    fn queue_tasks_changed(&self) {
+        if self.imp().tasks_changed_queued.replace(true) {
+            return;
+        }
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move || {
+                todo.imp().tasks_changed_queued.set(false);
+                todo.emit_by_name::<()>("tasks-changed", &[]);
+            }
+        ));
+    }
+
+    /// Connects to changes of tasks in any collection.
+    pub(crate) fn connect_tasks_changed<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "tasks-changed",
+            false,
+            glib::closure_local!(move |todo: &Self| f(todo)),
+        )
+    }
+
+    /// Refilters, resorts and regroups the tasks of the current collection.
+    fn refresh_task_list(&self) {
+        let imp = self.imp();
+        if let Some(filter) = imp
             .current_filter_model
             .borrow()
-            .clone()
-            .expect("`current_filter_model` should be set in `set_current_collection`.")
-            .set_filter(self.filter().as_ref());
+            .as_ref()
+            .and_then(|filter_model| filter_model.filter())
+        {
+            filter.changed(gtk::FilterChange::Different);
+        }
+        if let Some(sorter) = imp
+            .current_sort_model
+            .borrow()
+            .as_ref()
+            .and_then(|sort_model| sort_model.sorter())
+        {
+            sorter.changed(gtk::SorterChange::Different);
+        }
+        imp.tasks_list.invalidate_headers();
+        imp.board.queue_refresh();
+        self.update_layout();
+    }
+
+    /// Shows the list or the board, as chosen for the current collection.
+    fn update_layout(&self) {
+        let layout = self
+            .imp()
+            .current_collection
+            .borrow()
+            .as_ref()
+            .map_or(TaskLayout::List, |collection| {
+                collection.view_options().layout
+            });
+        self.imp().layout_stack.set_visible_child_name(layout.id());
     }
     // ANCHOR_END: helper
 
-    fn filter(&self) -> Option<CustomFilter> {
-        // Get filter state from settings
-        let app_settings = RnApp::default().app_settings();
-        let filter_state = app_settings?.string("filter");
+    /// Hides completed tasks, unless the collection shows them.
+    fn filter(collection: &CollectionObject) -> CustomFilter {
+        CustomFilter::new(clone!(
+            #[weak]
+            collection,
+            #[upgrade_or]
+            true,
+            move |obj| {
+                // Get `TaskObject` from `glib::Object`
+                let task_object = obj
+                    .downcast_ref::<TaskObject>()
+                    .expect("The object needs to be of type `TaskObject`.");
 
-        // Create custom filters
-        let filter_open = CustomFilter::new(|obj| {
-            // Get `TaskObject` from `glib::Object`
-            let task_object = obj
-                .downcast_ref::<TaskObject>()
-                .expect("The object needs to be of type `TaskObject`.");
+                collection.view_options().show_completed || !task_object.is_completed()
+            }
+        ))
+    }
 
-            // Only allow completed tasks
-            !task_object.is_completed()
-        });
-        let filter_done = CustomFilter::new(|obj| {
-            // Get `TaskObject` from `glib::Object`
-            let task_object = obj
-                .downcast_ref::<TaskObject>()
-                .expect("The object needs to be of type `TaskObject`.");
+    /// Orders tasks by their group first, then by the sort order of the collection.
+    fn sorter(collection: &CollectionObject) -> CustomSorter {
+        CustomSorter::new(clone!(
+            #[weak]
+            collection,
+            #[upgrade_or]
+            gtk::Ordering::Equal,
+            move |a, b| {
+                let a = a
+                    .downcast_ref::<TaskObject>()
+                    .expect("The object needs to be of type `TaskObject`.");
+                let b = b
+                    .downcast_ref::<TaskObject>()
+                    .expect("The object needs to be of type `TaskObject`.");
+                let options = collection.view_options();
+                let today = Local::now().date_naive();
 
-            // Only allow done tasks
-            task_object.is_completed()
-        });
+                let group_ordering = if options.group == TaskGroup::None {
+                    Ordering::Equal
+                } else {
+                    task_group(a, options.group, today)
+                        .0
+                        .cmp(&task_group(b, options.group, today).0)
+                };
+                group_ordering
+                    .then_with(|| match options.sort {
+                        TaskSort::Manual => Ordering::Equal,
+                        TaskSort::Due => due_order(a).cmp(&due_order(b)),
+                        TaskSort::Priority => b.priority().cmp(&a.priority()),
+                        TaskSort::Alphabetical => {
+                            a.content().to_lowercase().cmp(&b.content().to_lowercase())
+                        }
+                        TaskSort::Created => b.created_at().cmp(&a.created_at()),
+                    })
+                    .into()
+            }
+        ))
+    }
+
+    /// Shows a header above the first task of each group.
+    fn update_task_header(&self, row: &ListBoxRow, before: Option<&ListBoxRow>) {
+        let group = self
+            .imp()
+            .current_collection
+            .borrow()
+            .as_ref()
+            .map_or(TaskGroup::None, |collection| {
+                collection.view_options().group
+            });
+        let Some(sort_model) = self.imp().current_sort_model.borrow().clone() else {
+            return;
+        };
+        if group == TaskGroup::None {
+            row.set_header(None::<&gtk::Widget>);
+            return;
+        }
 
-        // Return the correct filter
-        match filter_state.as_str() {
-            "All" => None,
-            "Open" => Some(filter_open),
-            "Done" => Some(filter_done),
-            _ => unreachable!(),
+        let today = Local::now().date_naive();
+        let group_of = |row: &ListBoxRow| {
+            sort_model
+                .item(row.index() as u32)
+                .and_downcast::<TaskObject>()
+                .map(|task_object| task_group(&task_object, group, today))
+        };
+        let Some((order, title)) = group_of(row) else {
+            return;
+        };
+        if before
+            .and_then(group_of)
+            .is_some_and(|(before_order, _)| before_order == order)
+        {
+            row.set_header(None::<&gtk::Widget>);
+            return;
+        }
+        let header = Label::builder()
+            .label(title)
+            .xalign(0.0)
+            .margin_top(12)
+            .margin_bottom(6)
+            .margin_start(12)
+            .build();
+        header.add_css_class("heading");
+        row.set_header(Some(&header));
+    }
+
+    fn setup_view_actions(&self) {
+        let actions = gio::SimpleActionGroup::new();
+
+        let action_sort = gio::SimpleAction::new_stateful(
+            "sort",
+            Some(glib::VariantTy::STRING),
+            &TaskSort::default().id().to_variant(),
+        );
+        action_sort.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, parameter| {
+                let Some(sort) = parameter
+                    .and_then(|parameter| parameter.str())
+                    .and_then(TaskSort::from_id)
+                else {
+                    return;
+                };
+                action.set_state(&sort.id().to_variant());
+                todo.update_view_options(|options| options.sort = sort);
+            }
+        ));
+        actions.add_action(&action_sort);
+
+        let action_group = gio::SimpleAction::new_stateful(
+            "group",
+            Some(glib::VariantTy::STRING),
+            &TaskGroup::default().id().to_variant(),
+        );
+        action_group.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, parameter| {
+                let Some(group) = parameter
+                    .and_then(|parameter| parameter.str())
+                    .and_then(TaskGroup::from_id)
+                else {
+                    return;
+                };
+                action.set_state(&group.id().to_variant());
+                todo.update_view_options(|options| options.group = group);
+            }
+        ));
+        actions.add_action(&action_group);
+
+        let action_show_completed =
+            gio::SimpleAction::new_stateful("show-completed", None, &true.to_variant());
+        action_show_completed.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, _| {
+                let show_completed = !action
+                    .state()
+                    .and_then(|state| state.get::<bool>())
+                    .unwrap_or(true);
+                action.set_state(&show_completed.to_variant());
+                todo.update_view_options(|options| {
+                    options.show_completed = show_completed
+                });
+            }
+        ));
+        actions.add_action(&action_show_completed);
+
+        let action_layout = gio::SimpleAction::new_stateful(
+            "layout",
+            Some(glib::VariantTy::STRING),
+            &TaskLayout::default().id().to_variant(),
+        );
+        action_layout.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, parameter| {
+                let Some(layout) = parameter
+                    .and_then(|parameter| parameter.str())
+                    .and_then(TaskLayout::from_id)
+                else {
+                    return;
+                };
+                action.set_state(&layout.id().to_variant());
+                todo.update_view_options(|options| options.layout = layout);
+            }
+        ));
+        actions.add_action(&action_layout);
+
+        let action_board_columns = gio::SimpleAction::new_stateful(
+            "board-columns",
+            Some(glib::VariantTy::STRING),
+            &BoardColumns::default().id().to_variant(),
+        );
+        action_board_columns.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, parameter| {
+                let Some(board_columns) = parameter
+                    .and_then(|parameter| parameter.str())
+                    .and_then(BoardColumns::from_id)
+                else {
+                    return;
+                };
+                action.set_state(&board_columns.id().to_variant());
+                todo.update_view_options(|options| {
+                    options.board_columns = board_columns
+                });
+            }
+        ));
+        actions.add_action(&action_board_columns);
+
+        self.insert_action_group("collection", Some(&actions));
+        self.imp()
+            .view_actions
+            .set(actions)
+            .expect("`view_actions` should only be set once.");
+    }
+
+    fn update_view_options(&self, f: impl FnOnce(&mut ViewOptions)) {
+        let Some(collection) = self.imp().current_collection.borrow().clone() else {
+            return;
+        };
+        let mut options = collection.view_options();
+        f(&mut options);
+        collection.set_view_options(options);
+        self.refresh_task_list();
+    }
+
+    /// Shows the view options of the collection in the states of the `collection` actions.
+    fn sync_view_actions(&self, collection: &CollectionObject) {
+        let Some(actions) = self.imp().view_actions.get() else {
+            return;
+        };
+        let options = collection.view_options();
+        for (name, state) in [
+            ("sort", options.sort.id().to_variant()),
+            ("group", options.group.id().to_variant()),
+            ("show-completed", options.show_completed.to_variant()),
+            ("layout", options.layout.id().to_variant()),
+            ("board-columns", options.board_columns.id().to_variant()),
+        ] {
+            if let Some(action) = actions
+                .lookup_action(name)
+                .and_downcast::<gio::SimpleAction>()
+            {
+                action.set_state(&state);
+            }
         }
     }
 
@@ -101,6 +408,28 @@ impl RnTodo {
             .set(collections.clone())
             .expect("Could not set collections");
 
+        collections.connect_items_changed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |collections, position, _, added| {
+                for collection in (position..position + added).filter_map(|i| {
+                    collections.item(i).and_downcast::<CollectionObject>()
+                }) {
+                    collection.connect_tasks_changed(clone!(
+                        #[weak]
+                        todo,
+                        move |_| todo.queue_tasks_changed()
+                    ));
+                    collection.connect_title_notify(clone!(
+                        #[weak]
+                        todo,
+                        move |_| todo.queue_tasks_changed()
+                    ));
+                }
+                todo.queue_tasks_changed();
+            }
+        ));
+
         self.imp().collections_list.bind_model(
             Some(&collections),
             clone!(
@@ -120,28 +449,47 @@ impl RnTodo {
     // ANCHOR_END: setup_collections
 
     // ANCHOR: restore_data
-    fn restore_data(&self) {
-        if let Ok(file) = File::open(data_path()) {
-            // Deserialize data from file to vector
-            let backup_data: Vec<CollectionData> = serde_json::from_reader(file)
-                .expect(
-                    "It should be possible to read `backup_data` from the json file.",
-                );
-
-            // Convert `Vec<CollectionData>` to `Vec<CollectionObject>`
-            let collections: Vec<CollectionObject> = backup_data
-                .into_iter()
-                .map(CollectionObject::from_collection_data)
-                .collect();
+    /// Restores the collections from an unencrypted data file.
+    ///
+    /// Encrypted data files are restored through [RnTodo::unlock].
+    pub(crate) fn restore_data(&self) {
+        let todo_data: TodoData = match File::open(data_path()) {
+            // Deserialize data from file
+            Ok(file) => serde_json::from_reader(file).expect(
+                "It should be possible to read `todo_data` from the json file.",
+            ),
+            Err(_) => TodoData::default(),
+        };
+        self.restore_templates(todo_data.templates);
+        self.restore_collections(todo_data.collections);
+    }
 
-            // Insert restored objects into model
-            self.collections().extend_from_slice(&collections);
+    fn restore_collections(&self, backup_data: Vec<CollectionData>) {
+        // Convert `Vec<CollectionData>` to `Vec<CollectionObject>`
+        let mut collections: Vec<CollectionObject> = backup_data
+            .into_iter()
+            .map(CollectionObject::from_collection_data)
+            .collect();
 
-            // Set first collection as current
-            if let Some(first_collection) = collections.first() {
-                self.set_current_collection(first_collection.clone());
+        // The Inbox always exists and comes first
+        match collections.iter().position(CollectionObject::is_inbox) {
+            Some(position) => {
+                let inbox = collections.remove(position);
+                collections.insert(0, inbox);
             }
+            None => collections.insert(0, CollectionObject::new_inbox()),
         }
+
+        // Insert restored objects into model
+        self.collections().extend_from_slice(&collections);
+
+        // Set first collection as current
+        if let Some(first_collection) = collections.first() {
+            self.set_current_collection(first_collection.clone());
+        }
+        self.clear_history();
+        // The runs that were due while the app was closed can be undone
+        self.run_scheduled_templates();
     }
     // ANCHOR_END: restore_data
 
@@ -160,7 +508,70 @@ impl RnTodo {
             .sync_create()
             .build();
 
-        ListBoxRow::builder().child(&label).build()
+        let row_box = gtk::Box::builder().spacing(6).build();
+        if collection_object.is_inbox() {
+            row_box.append(&gtk::Image::from_icon_name("mailbox-symbolic"));
+        }
+        row_box.append(&label);
+        let row = ListBoxRow::builder().child(&row_box).build();
+
+        // Collections are reordered by dragging them onto another one, the Inbox stays
+        // first
+        if !collection_object.is_inbox() {
+            let drag_source = gtk::DragSource::builder()
+                .actions(gdk::DragAction::MOVE)
+                .build();
+            drag_source.connect_prepare(clone!(
+                #[weak]
+                collection_object,
+                #[upgrade_or]
+                None,
+                move |_, _, _| {
+                    Some(gdk::ContentProvider::for_value(
+                        &collection_object.to_value(),
+                    ))
+                }
+            ));
+            row.add_controller(drag_source);
+        }
+
+        // Dropping a task moves it to the end of the collection
+        let drop_target =
+            gtk::DropTarget::new(glib::Type::INVALID, gdk::DragAction::MOVE);
+        drop_target
+            .set_types(&[TaskObject::static_type(), CollectionObject::static_type()]);
+        drop_target.connect_drop(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            collection_object,
+            #[upgrade_or]
+            false,
+            move |_, value, _, _| {
+                if let Ok(task_object) = value.get::<TaskObject>() {
+                    todo.move_task(&task_object, &collection_object);
+                    return true;
+                }
+                let Ok(dragged) = value.get::<CollectionObject>() else {
+                    return false;
+                };
+                // The rows are replaced, which can't happen during the drop
+                glib::idle_add_local_once(clone!(
+                    #[weak]
+                    todo,
+                    #[weak]
+                    collection_object,
+                    move || {
+                        todo.reorder_collection(&dragged, &collection_object);
+                    }
+                ));
+                true
+            }
+        ));
+        row.add_controller(drop_target);
+        self.add_collection_context_menu(&row, collection_object);
+
+        row
     }
     // ANCHOR_END: create_collection_row
 
@@ -168,8 +579,13 @@ impl RnTodo {
     fn set_current_collection(&self, collection: CollectionObject) {
         // Wrap model with filter and selection and pass it to the list box
         let tasks = collection.tasks();
-        let filter_model = FilterListModel::new(Some(tasks.clone()), self.filter());
-        let selection_model = NoSelection::new(Some(filter_model.clone()));
+        let filter_model =
+            FilterListModel::new(Some(tasks.clone()), Some(Self::filter(&collection)));
+        let sort_model = SortListModel::new(
+            Some(filter_model.clone()),
+            Some(Self::sorter(&collection)),
+        );
+        let selection_model = MultiSelection::new(Some(sort_model.clone()));
         self.imp().tasks_list.bind_model(
             Some(&selection_model),
             clone!(
@@ -181,13 +597,56 @@ impl RnTodo {
                         .downcast_ref()
                         .expect("The object should be of type `TaskObject`.");
                     let row = window.create_task_row(task_object);
+                    add_task_drag_source(&row, task_object);
+                    // Dropping onto a row places the task above it
+                    add_task_drop_target(
+                        &row,
+                        clone!(
+                            #[weak]
+                            window,
+                            #[weak]
+                            task_object,
+                            move |dropped_task| {
+                                window.queue_reorder_task(
+                                    dropped_task,
+                                    Some(task_object),
+                                );
+                            }
+                        ),
+                    );
                     row.upcast()
                 }
             ),
         );
 
-        // Store filter model
+        // Store filter and sort model
         self.imp().current_filter_model.replace(Some(filter_model));
+        self.imp()
+            .board
+            .set_tasks(Some(&collection), Some(sort_model.upcast_ref()));
+        self.imp().current_sort_model.replace(Some(sort_model));
+        self.imp()
+            .current_selection_model
+            .replace(Some(selection_model));
+        self.update_selection();
+        self.sync_view_actions(&collection);
+
+        // Tasks move when their due date, priority or content changes
+        if let Some((old_collection, handler_id)) =
+            self.imp().collection_changed_handler.take()
+        {
+            old_collection.disconnect(handler_id);
+        }
+        let collection_changed_handler_id = collection.connect_tasks_changed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_| {
+                todo.refresh_task_list();
+            }
+        ));
+        self.imp()
+            .collection_changed_handler
+            .replace(Some((collection.clone(), collection_changed_handler_id)));
 
         // If present, disconnect old `tasks_changed` handler
         if let Some(handler_id) = self.imp().tasks_changed_handler_id.take() {
@@ -209,11 +668,74 @@ impl RnTodo {
 
         // Set current tasks
         self.imp().current_collection.replace(Some(collection));
+        self.update_layout();
 
         self.select_collection_row();
     }
     // ANCHOR_END: set_current_collection
 
+    /// Moves the task above `before` in the current collection, or to its end.
+    ///
+    /// Tasks from other collections are moved into it. The tasks are then sorted
+    /// manually, so that the new order shows. It happens once the main loop is idle, as
+    /// the rows of the drop are replaced.
+    fn queue_reorder_task(&self, task_object: TaskObject, before: Option<TaskObject>) {
+        if before.as_ref() == Some(&task_object) {
+            return;
+        }
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move || {
+                let Some(collection) = todo.imp().current_collection.borrow().clone()
+                else {
+                    return;
+                };
+                for collection in todo
+                    .collections()
+                    .iter::<CollectionObject>()
+                    .filter_map(Result::ok)
+                {
+                    collection.remove_task(&task_object);
+                }
+                let tasks = collection.tasks();
+                let position = before
+                    .and_then(|before| tasks.find(&before))
+                    .unwrap_or(tasks.n_items());
+                tasks.insert(position, &task_object);
+
+                if collection.view_options().sort != TaskSort::Manual {
+                    todo.update_view_options(|options| options.sort = TaskSort::Manual);
+                    todo.sync_view_actions(&collection);
+                }
+            }
+        ));
+    }
+
+    /// Moves the collection to the position of `before`, the Inbox stays first.
+    fn reorder_collection(
+        &self,
+        collection: &CollectionObject,
+        before: &CollectionObject,
+    ) {
+        let collections = self.collections();
+        if collection == before || collection.is_inbox() {
+            return;
+        }
+        let (Some(from), Some(mut to)) =
+            (collections.find(collection), collections.find(before))
+        else {
+            return;
+        };
+        // `before` moves up once the collection is removed above it
+        if from < to {
+            to -= 1;
+        }
+        collections.remove(from);
+        collections.insert(to.max(1), collection);
+        self.select_collection_row();
+    }
+
     // ANCHOR: set_task_list_visible
     fn set_task_list_visible(&self, tasks: &gio::ListStore) {
         self.imp().tasks_list.set_visible(tasks.n_items() > 0);
@@ -229,18 +751,238 @@ impl RnTodo {
     }
     // ANCHOR_END: select_collection_row
 
-    fn create_task_row(&self, task_object: &TaskObject) -> ActionRow {
+    /// The Inbox collection, `None` while the data is locked.
+    pub(crate) fn inbox(&self) -> Option<CollectionObject> {
+        self.collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .find(CollectionObject::is_inbox)
+    }
+
+    /// The first collection with the title.
+    pub(crate) fn collection_by_title(&self, title: &str) -> Option<CollectionObject> {
+        self.collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .find(|collection| collection.title() == title)
+    }
+
+    /// Adds the task to the collection, or to the Inbox when there is no target collection.
+    pub(crate) fn add_task(
+        &self,
+        task_object: &TaskObject,
+        collection: Option<&CollectionObject>,
+    ) {
+        match collection.cloned().or_else(|| self.inbox()) {
+            Some(collection) => collection.tasks().append(task_object),
+            None => error!("Can't add task while the data is locked"),
+        }
+    }
+
+    /// Moves the task to the end of another collection.
+    pub(crate) fn move_task(&self, task_object: &TaskObject, to: &CollectionObject) {
+        for collection in self
+            .collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+        {
+            collection.remove_task(task_object);
+        }
+        to.tasks().append(task_object);
+    }
+
+    /// Opens the collection, as if its row was activated.
+    pub(crate) fn show_collection(&self, collection: &CollectionObject) {
+        self.set_current_collection(collection.clone());
+        self.imp().split_view.set_show_content(true);
+    }
+
+    /// Opens the collection of the task and focuses the row of the task.
+    pub(crate) fn show_task(
+        &self,
+        collection: &CollectionObject,
+        task_object: &TaskObject,
+    ) {
+        self.show_collection(collection);
+        let Some(sort_model) = self.imp().current_sort_model.borrow().clone() else {
+            return;
+        };
+        let Some(position) = sort_model
+            .iter::<TaskObject>()
+            .filter_map(Result::ok)
+            .position(|shown_task| shown_task == *task_object)
+        else {
+            return;
+        };
+        // The row can only take the focus once the page is shown
+        glib::idle_add_local_once(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move || {
+                if let Some(row) = todo.imp().tasks_list.row_at_index(position as i32) {
+                    row.grab_focus();
+                }
+            }
+        ));
+    }
+
+    /// Shows the task in the detail pane, opening its collection if it isn't listed.
+    pub(crate) fn open_task_detail(&self, task_object: &TaskObject) {
+        let imp = self.imp();
+        if let Some(appwindow) = self.root().and_downcast::<RnAppWindow>() {
+            appwindow.views_stack().set_visible_child_name("done_page");
+        }
+        let sort_model = imp.current_sort_model.borrow().clone();
+        let listed = sort_model.is_some_and(|sort_model| {
+            sort_model
+                .iter::<TaskObject>()
+                .filter_map(Result::ok)
+                .any(|shown_task| shown_task == *task_object)
+        });
+        match self.collection_of(task_object) {
+            Some(collection) if !listed => self.show_task(&collection, task_object),
+            _ => imp.split_view.set_show_content(true),
+        }
+
+        let task_detail = imp.task_detail.get();
+        task_detail.insert_action_group("task", Some(&self.task_actions(task_object)));
+        task_detail.set_task(Some(task_object));
+        if imp.detail_sheet.sheet().is_some() {
+            imp.detail_sheet.set_open(true);
+        } else {
+            imp.detail_split_view.set_show_sidebar(true);
+        }
+    }
+
+    pub(crate) fn close_task_detail(&self) {
+        let imp = self.imp();
+        imp.detail_split_view.set_show_sidebar(false);
+        imp.detail_sheet.set_open(false);
+        imp.task_detail.set_task(None);
+        imp.task_detail
+            .insert_action_group("task", None::<&gio::ActionGroup>);
+    }
+
+    /// Moves the detail pane into a bottom sheet on narrow widths, and back.
+    fn setup_task_detail(&self) {
+        let imp = self.imp();
+        imp.detail_breakpoint.connect_apply(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_| {
+                todo.set_task_detail_in_sheet(true);
+            }
+        ));
+        imp.detail_breakpoint.connect_unapply(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_| {
+                todo.set_task_detail_in_sheet(false);
+            }
+        ));
+    }
+
+    /// Checks twice a minute for reminders of open tasks that passed.
+    fn setup_reminders(&self) {
+        self.imp().reminders_checked_at.set(Some(Local::now()));
+        glib::timeout_add_seconds_local(
+            30,
+            clone!(
+                #[weak(rename_to = todo)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    todo.send_reminders();
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    /// Sends a notification for each reminder since the last check.
+    fn send_reminders(&self) {
+        let now = Local::now();
+        let Some(checked_at) = self.imp().reminders_checked_at.replace(Some(now))
+        else {
+            return;
+        };
+        let Some(app) = gio::Application::default() else {
+            return;
+        };
+        for (collection, task_object) in self.all_tasks() {
+            if task_object.is_completed()
+                || !task_object
+                    .reminders()
+                    .0
+                    .iter()
+                    .any(|reminder| checked_at < *reminder && *reminder <= now)
+            {
+                continue;
+            }
+            let notification = gio::Notification::new(&task_object.content());
+            notification.set_body(Some(&collection.title()));
+            app.send_notification(None, &notification);
+        }
+    }
+
+    fn set_task_detail_in_sheet(&self, in_sheet: bool) {
+        let imp = self.imp();
+        let task_detail = imp.task_detail.get();
+        let open = imp.detail_split_view.shows_sidebar() || imp.detail_sheet.is_open();
+        if in_sheet {
+            imp.detail_split_view.set_show_sidebar(false);
+            imp.detail_split_view.set_sidebar(None::<&gtk::Widget>);
+            imp.detail_sheet.set_sheet(Some(&task_detail));
+            imp.detail_sheet.set_open(open);
+        } else {
+            imp.detail_sheet.set_open(false);
+            imp.detail_sheet.set_sheet(None::<&gtk::Widget>);
+            imp.detail_split_view.set_sidebar(Some(&task_detail));
+            imp.detail_split_view.set_show_sidebar(open);
+        }
+    }
+
+    pub(crate) fn create_task_row(&self, task_object: &TaskObject) -> ActionRow {
         // Create check button
-        let check_button = CheckButton::builder()
-            .valign(Align::Center)
-            .can_focus(false)
-            .build();
+        let check_button = CheckButton::builder().valign(Align::Center).build();
 
-        // Create row
-        let row = ActionRow::builder()
-            .activatable_widget(&check_button)
-            .build();
+        // Create row, activating it opens the task in the detail pane
+        let row = ActionRow::builder().activatable(true).build();
         row.add_prefix(&check_button);
+        row.connect_activated(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_| {
+                todo.open_task_detail(&task_object);
+            }
+        ));
+
+        // The `task` actions, from the context menu and the move button
+        row.insert_action_group("task", Some(&self.task_actions(task_object)));
+        self.add_task_context_menu(&row, task_object);
+
+        // Move the task to another collection, listed when the menu is opened
+        let move_button = gtk::MenuButton::builder()
+            .icon_name("send-to-symbolic")
+            .tooltip_text("Move to Collection")
+            .valign(Align::Center)
+            .build();
+        move_button.add_css_class("flat");
+        move_button.set_create_popup_func(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |move_button| {
+                let collection = todo.collection_of(&task_object);
+                move_button
+                    .set_menu_model(Some(&todo.move_menu("task", collection.as_ref())));
+            }
+        ));
+        row.add_suffix(&move_button);
 
         // Bind properties
         task_object
@@ -258,6 +1000,35 @@ impl RnTodo {
     }
 
     fn setup_callbacks(&self) {
+        self.imp().tasks_list.set_header_func(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |row, before| {
+                todo.update_task_header(row, before);
+            }
+        ));
+
+        // Dropping below the rows moves the task to the end of the collection
+        add_task_drop_target(
+            &self.imp().tasks_list.get(),
+            clone!(
+                #[weak(rename_to = todo)]
+                self,
+                move |task_object| {
+                    todo.queue_reorder_task(task_object, None);
+                }
+            ),
+        );
+
+        // Show what the entry text is parsed into while it is typed
+        self.imp().entry.connect_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                window.update_quick_add_preview();
+            }
+        ));
+
         // Setup callback for activation of the entry
         self.imp().entry.connect_activate(clone!(
             #[weak(rename_to = window)]
@@ -315,22 +1086,77 @@ impl RnTodo {
     }
     // ANCHOR_END: set_stack
 
+    fn parse_quick_add(&self, text: &str) -> QuickAdd {
+        let collection_titles: Vec<String> = self
+            .collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .map(|collection| collection.title())
+            .collect();
+        let app_settings = self
+            .root()
+            .and_downcast::<RnAppWindow>()
+            .and_then(|appwindow| appwindow.app().app_settings());
+        QuickAdd::parse(
+            text,
+            Local::now().date_naive(),
+            &collection_titles,
+            DateLanguage::from_settings(app_settings.as_ref()),
+        )
+    }
+
+    fn update_quick_add_preview(&self) {
+        let imp = self.imp();
+        let text = imp.entry.text();
+        let quick_add = self.parse_quick_add(&text);
+
+        // Highlight the recognized parts of the text
+        let attributes = pango::AttrList::new();
+        for token in &quick_add.tokens {
+            let mut weight = pango::AttrInt::new_weight(pango::Weight::Bold);
+            weight.set_start_index(token.range.start as u32);
+            weight.set_end_index(token.range.end as u32);
+            attributes.insert(weight);
+            let mut underline = pango::AttrInt::new_underline(pango::Underline::Single);
+            underline.set_start_index(token.range.start as u32);
+            underline.set_end_index(token.range.end as u32);
+            attributes.insert(underline);
+        }
+        imp.entry.set_attributes(&attributes);
+
+        let preview = imp.quick_add_preview.get();
+        while let Some(child) = preview.first_child() {
+            preview.remove(&child);
+        }
+        for token in &quick_add.tokens {
+            let chip = Label::new(Some(&token.display));
+            chip.add_css_class("board_chip");
+            preview.append(&chip);
+        }
+        preview.set_visible(!quick_add.tokens.is_empty());
+    }
+
     fn new_task(&self) {
         // Get content from entry and clear it
         let buffer = self.imp().entry.buffer();
-        let content = buffer.text().to_string();
-        if content.is_empty() {
+        let quick_add = self.parse_quick_add(&buffer.text());
+        if quick_add.content.is_empty() {
             return;
         }
         buffer.set_text("");
 
-        // Add new task to model
-        let task = TaskObject::new(false, content);
-        self.tasks().append(&task);
+        // Add new task to the collection it names, or else to the current one
+        let task = quick_add.to_task();
+        let collection = quick_add
+            .collection
+            .and_then(|title| self.collection_by_title(&title))
+            .or_else(|| self.imp().current_collection.borrow().clone());
+        self.add_task(&task, collection.as_ref());
     }
 
     fn remove_done_tasks(&self) {
         let tasks = self.tasks();
+        let n_tasks = tasks.n_items();
         let mut position = 0;
         while let Some(item) = tasks.item(position) {
             // Get `TaskObject` from `glib::Object`
@@ -344,6 +1170,9 @@ impl RnTodo {
                 position += 1;
             }
         }
+        if tasks.n_items() < n_tasks {
+            self.show_undo_toast("Completed tasks removed");
+        }
     }
 
     // ANCHOR: new_collection
@@ -411,17 +1240,180 @@ impl RnTodo {
     }
     // ANCHOR_END: new_collection
     pub fn save_data(&self) {
+        // Never overwrite the encrypted data file while it is not loaded
+        if self.imp().locked.get() {
+            return;
+        }
+        if let Err(e) = self.write_data() {
+            error!("Failed to save data, Err: {e:?}");
+        }
+    }
+
+    fn write_data(&self) -> anyhow::Result<()> {
+        // The collections aren't loaded, writing them would empty the data file
+        if self.is_locked() {
+            anyhow::bail!("The data is locked");
+        }
         // Store task data in vector
-        let backup_data: Vec<CollectionData> = self
+        let collections: Vec<CollectionData> = self
             .collections()
             .iter::<CollectionObject>()
             .filter_map(|collection_object| collection_object.ok())
             .map(|collection_object| collection_object.to_collection_data())
             .collect();
+        let todo_data = TodoData {
+            collections,
+            templates: self.imp().templates.borrow().clone(),
+        };
+
+        let mut bytes = serde_json::to_vec_pretty(&todo_data)?;
+        if let Some(data_key) = self.imp().data_key.borrow().as_ref() {
+            bytes = data_key.encrypt(&bytes)?;
+        }
+
+        write_data_file(&bytes)
+    }
+
+    /// Whether the data file is encrypted with a passphrase.
+    pub(crate) fn is_encrypted(&self) -> bool {
+        self.imp().data_key.borrow().is_some()
+    }
+
+    pub(crate) fn is_locked(&self) -> bool {
+        self.imp().locked.get()
+    }
+
+    pub(crate) fn passphrase_matches(&self, passphrase: &str) -> bool {
+        self.imp()
+            .data_key
+            .borrow()
+            .as_ref()
+            .is_some_and(|data_key| data_key.matches(passphrase))
+    }
+
+    /// Marks the data as locked without saving, used when starting with an encrypted data file.
+    pub(crate) fn set_locked(&self) {
+        self.imp().locked.set(true);
+    }
+
+    /// Decrypts the data file with the passphrase and restores the collections.
+    pub(crate) fn unlock(&self, passphrase: &str) -> anyhow::Result<()> {
+        let bytes = std::fs::read(data_path()).context("Failed to read data file")?;
+        let (plaintext, data_key) = crypto::decrypt(&bytes, passphrase)?;
+        let todo_data: TodoData = serde_json::from_slice(&plaintext)?;
 
-        // Save state to file
-        let file = File::create(data_path()).expect("Could not create json file.");
-        serde_json::to_writer_pretty(file, &backup_data)
-            .expect("Could not write data to json file");
+        self.imp().data_key.replace(Some(data_key));
+        self.imp().locked.set(false);
+        self.restore_templates(todo_data.templates);
+        self.restore_collections(todo_data.collections);
+        Ok(())
+    }
+
+    /// Saves the data and removes it from memory until [RnTodo::unlock] is called.
+    pub(crate) fn lock(&self) {
+        if self.is_locked() || !self.is_encrypted() {
+            return;
+        }
+        self.save_data();
+        self.imp().locked.set(true);
+
+        if let Some(handler_id) = self.imp().tasks_changed_handler_id.take() {
+            self.tasks().disconnect(handler_id);
+        }
+        self.imp()
+            .tasks_list
+            .bind_model(None::<&gio::ListModel>, |_| unreachable!());
+        if let Some((collection, handler_id)) =
+            self.imp().collection_changed_handler.take()
+        {
+            collection.disconnect(handler_id);
+        }
+        self.imp().current_filter_model.replace(None);
+        self.imp().current_sort_model.replace(None);
+        self.imp().current_selection_model.replace(None);
+        self.set_selection_mode(false);
+        self.imp().board.set_tasks(None, None);
+        self.close_task_detail();
+        self.imp().current_collection.replace(None);
+        self.imp().data_key.replace(None);
+        self.collections().remove_all();
+        self.restore_templates(Vec::new());
+        self.clear_history();
+    }
+
+    /// Encrypts the data file and its backup with a new passphrase, or decrypts them when `None`.
+    pub(crate) fn set_passphrase(
+        &self,
+        passphrase: Option<&str>,
+    ) -> anyhow::Result<()> {
+        if self.is_locked() {
+            anyhow::bail!("Can't change the passphrase while the data is locked");
+        }
+        let data_key = passphrase.map(DataKey::new).transpose()?;
+        self.imp().data_key.replace(data_key);
+        self.write_data()?;
+
+        // The backup still holds the data in its previous form
+        std::fs::copy(data_path(), backup_path()).context("Failed to update backup")?;
+        Ok(())
+    }
+}
+
+/// Orders tasks by day, then by time with untimed tasks last, tasks without a due date last.
+fn due_order(task_object: &TaskObject) -> impl Ord {
+    let due = task_object.due();
+    (
+        due.is_none(),
+        due.map(|due| due.date),
+        due.and_then(|due| due.time).is_none(),
+        due.and_then(|due| due.time),
+    )
+}
+
+/// The position of the group of the task among all groups, and the title of its header.
+fn task_group(
+    task_object: &TaskObject,
+    group: TaskGroup,
+    today: NaiveDate,
+) -> ((u8, i64, String), String) {
+    match group {
+        TaskGroup::None => ((0, 0, String::new()), String::new()),
+        TaskGroup::Section => {
+            let section = task_object.section();
+            if section.is_empty() {
+                ((0, 0, String::new()), String::from("No Section"))
+            } else {
+                ((1, 0, section.to_lowercase()), section)
+            }
+        }
+        TaskGroup::Due => match task_object.due_date() {
+            None => ((2, 0, String::new()), String::from("No Due Date")),
+            Some(date) if date < today => {
+                ((0, 0, String::new()), String::from("Overdue"))
+            }
+            Some(date) => {
+                let title = if date == today {
+                    String::from("Today")
+                } else if date == today + Days::new(1) {
+                    String::from("Tomorrow")
+                } else if date.year() == today.year() {
+                    date.format("%A, %B %-d").to_string()
+                } else {
+                    date.format("%A, %B %-d, %Y").to_string()
+                };
+                ((1, (date - today).num_days(), String::new()), title)
+            }
+        },
+        TaskGroup::Priority => match task_object.priority() {
+            0 => ((1, 0, String::new()), String::from("No Priority")),
+            priority => (
+                (0, -i64::from(priority), String::new()),
+                format!("Priority {priority}"),
+            ),
+        },
+        TaskGroup::Label => match task_object.labels().into_iter().next() {
+            Some(label) => ((0, 0, label.clone()), format!("#{label}")),
+            None => ((1, 0, String::new()), String::from("No Label")),
+        },
     }
 }
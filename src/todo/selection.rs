@@ -0,0 +1,455 @@
+// Imports
+use super::task_menu::{due_menu, parse_due_target, priority_menu, set_due_date};
+use super::RnTodo;
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{AlertDialog, ResponseAppearance};
+use chrono::NaiveDate;
+use gtk::{gdk, gio, glib, glib::clone, Entry};
+
+impl RnTodo {
+    /// Sets up the `selection` actions, which change all selected tasks at once.
+    pub(super) fn setup_selection(&self) {
+        let imp = self.imp();
+        let actions = gio::SimpleActionGroup::new();
+
+        let action_mode =
+            gio::SimpleAction::new_stateful("mode", None, &false.to_variant());
+        action_mode.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |action, _| {
+                let active = !action
+                    .state()
+                    .and_then(|state| state.get::<bool>())
+                    .unwrap_or(false);
+                todo.set_selection_mode(active);
+            }
+        ));
+        actions.add_action(&action_mode);
+
+        let action_select_all = gio::SimpleAction::new("select-all", None);
+        action_select_all.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                todo.set_selection_mode(true);
+                todo.imp().tasks_list.select_all();
+            }
+        ));
+        actions.add_action(&action_select_all);
+
+        let action_complete = gio::SimpleAction::new("complete", None);
+        action_complete.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                for task_object in todo.selected_tasks() {
+                    task_object.set_completed(true);
+                }
+            }
+        ));
+        actions.add_action(&action_complete);
+
+        let action_uncomplete = gio::SimpleAction::new("uncomplete", None);
+        action_uncomplete.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                for task_object in todo.selected_tasks() {
+                    task_object.set_completed(false);
+                }
+            }
+        ));
+        actions.add_action(&action_uncomplete);
+
+        let action_delete = gio::SimpleAction::new("delete", None);
+        action_delete.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    async move {
+                        todo.delete_selected_tasks().await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_delete);
+
+        let action_move_to =
+            gio::SimpleAction::new("move-to", Some(glib::VariantTy::UINT32));
+        action_move_to.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, parameter| {
+                let Some(collection) = parameter
+                    .and_then(|parameter| parameter.get::<u32>())
+                    .and_then(|position| todo.collections().item(position))
+                    .and_downcast::<CollectionObject>()
+                else {
+                    return;
+                };
+                for task_object in todo.selected_tasks() {
+                    todo.move_task(&task_object, &collection);
+                }
+            }
+        ));
+        actions.add_action(&action_move_to);
+
+        // The date as `%Y-%m-%d`, an empty string removes the due date
+        let action_set_due =
+            gio::SimpleAction::new("set-due", Some(glib::VariantTy::STRING));
+        action_set_due.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, parameter| {
+                if let Some(date) = parse_due_target(parameter) {
+                    todo.set_selected_due(date);
+                }
+            }
+        ));
+        actions.add_action(&action_set_due);
+
+        let action_choose_due = gio::SimpleAction::new("choose-due", None);
+        action_choose_due.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    async move {
+                        todo.choose_selected_due().await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_choose_due);
+
+        let action_set_priority =
+            gio::SimpleAction::new("set-priority", Some(glib::VariantTy::BYTE));
+        action_set_priority.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, parameter| {
+                let Some(priority) =
+                    parameter.and_then(|parameter| parameter.get::<u8>())
+                else {
+                    return;
+                };
+                for task_object in todo.selected_tasks() {
+                    task_object.set_priority(priority);
+                }
+            }
+        ));
+        actions.add_action(&action_set_priority);
+
+        let action_add_label = gio::SimpleAction::new("add-label", None);
+        action_add_label.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    async move {
+                        todo.add_label_to_selected().await;
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_add_label);
+
+        let action_remove_label =
+            gio::SimpleAction::new("remove-label", Some(glib::VariantTy::STRING));
+        action_remove_label.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_, parameter| {
+                let Some(label) = parameter.and_then(|parameter| parameter.str())
+                else {
+                    return;
+                };
+                for task_object in todo.selected_tasks() {
+                    task_object.remove_label(label);
+                }
+            }
+        ));
+        actions.add_action(&action_remove_label);
+
+        self.insert_action_group("selection", Some(&actions));
+        imp.selection_actions
+            .set(actions)
+            .expect("`selection_actions` should only be set once.");
+
+        imp.tasks_list.connect_selected_rows_changed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |_| {
+                todo.update_selection();
+            }
+        ));
+
+        // Ctrl and Shift clicks select rows, as in selection mode
+        let click_gesture = gtk::GestureClick::builder()
+            .propagation_phase(gtk::PropagationPhase::Capture)
+            .build();
+        click_gesture.connect_pressed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |gesture, _, _, _| {
+                let modifiers = gesture.current_event_state();
+                if modifiers.intersects(
+                    gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::SHIFT_MASK,
+                ) {
+                    todo.set_selection_mode(true);
+                }
+            }
+        ));
+        imp.tasks_list.add_controller(click_gesture);
+
+        let shortcuts = gtk::ShortcutController::new();
+        shortcuts.add_shortcut(gtk::Shortcut::new(
+            gtk::ShortcutTrigger::parse_string("Escape"),
+            Some(gtk::CallbackAction::new(clone!(
+                #[weak(rename_to = todo)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, _| {
+                    if !todo.in_selection_mode() {
+                        return glib::Propagation::Proceed;
+                    }
+                    todo.set_selection_mode(false);
+                    glib::Propagation::Stop
+                }
+            ))),
+        ));
+        imp.tasks_list.add_controller(shortcuts);
+
+        imp.selection_menu_button.set_create_popup_func(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            move |menu_button| {
+                menu_button.set_menu_model(Some(&todo.selection_menu()));
+            }
+        ));
+        self.update_selection();
+    }
+
+    fn in_selection_mode(&self) -> bool {
+        self.imp()
+            .selection_actions
+            .get()
+            .and_then(|actions| actions.lookup_action("mode"))
+            .and_then(|action| action.state())
+            .and_then(|state| state.get::<bool>())
+            .unwrap_or(false)
+    }
+
+    /// In selection mode clicks select rows instead of completing tasks.
+    pub(super) fn set_selection_mode(&self, active: bool) {
+        let imp = self.imp();
+        if let Some(action) = imp
+            .selection_actions
+            .get()
+            .and_then(|actions| actions.lookup_action("mode"))
+            .and_downcast::<gio::SimpleAction>()
+        {
+            action.set_state(&active.to_variant());
+        }
+        if active {
+            imp.tasks_list
+                .set_selection_mode(gtk::SelectionMode::Multiple);
+            imp.tasks_list.set_activate_on_single_click(false);
+        } else {
+            imp.tasks_list.unselect_all();
+            imp.tasks_list.set_selection_mode(gtk::SelectionMode::None);
+            imp.tasks_list.set_activate_on_single_click(true);
+        }
+        imp.selection_bar.set_revealed(active);
+    }
+
+    /// Mirrors the selected rows in the selection model and updates the selection bar.
+    pub(super) fn update_selection(&self) {
+        let imp = self.imp();
+        let Some(selection_model) = imp.current_selection_model.borrow().clone() else {
+            return;
+        };
+        let selected = gtk::Bitset::new_empty();
+        for row in imp.tasks_list.selected_rows() {
+            selected.add(row.index() as u32);
+        }
+        let mask = gtk::Bitset::new_range(0, selection_model.n_items());
+        selection_model.set_selection(&selected, &mask);
+
+        let count = selected.size();
+        imp.selection_label.set_label(&format!("{count} Selected"));
+        if let Some(actions) = imp.selection_actions.get() {
+            for name in [
+                "complete",
+                "uncomplete",
+                "delete",
+                "move-to",
+                "set-due",
+                "choose-due",
+                "set-priority",
+                "add-label",
+                "remove-label",
+            ] {
+                if let Some(action) = actions
+                    .lookup_action(name)
+                    .and_downcast::<gio::SimpleAction>()
+                {
+                    action.set_enabled(count > 0);
+                }
+            }
+        }
+        imp.selection_menu_button.set_sensitive(count > 0);
+    }
+
+    /// The selected tasks of the current collection, in the order they are listed.
+    pub(crate) fn selected_tasks(&self) -> Vec<TaskObject> {
+        let Some(selection_model) = self.imp().current_selection_model.borrow().clone()
+        else {
+            return Vec::new();
+        };
+        let selection = selection_model.selection();
+        (0..selection.size())
+            .filter_map(|i| selection_model.item(selection.nth(i as u32)))
+            .filter_map(|item| item.downcast::<TaskObject>().ok())
+            .collect()
+    }
+
+    /// Moving, due dates, priorities and labels, built when the menu is opened.
+    fn selection_menu(&self) -> gio::Menu {
+        let menu = gio::Menu::new();
+        let section = gio::Menu::new();
+        section.append(Some("Mark as _Open"), Some("selection.uncomplete"));
+        section.append(Some("Select _All"), Some("selection.select-all"));
+        menu.append_section(None, &section);
+
+        let current_collection = self.imp().current_collection.borrow().clone();
+        let label_menu = gio::Menu::new();
+        label_menu.append(Some("_Add Label…"), Some("selection.add-label"));
+        let mut labels: Vec<String> = self
+            .selected_tasks()
+            .iter()
+            .flat_map(TaskObject::labels)
+            .collect();
+        labels.sort();
+        labels.dedup();
+        let remove_section = gio::Menu::new();
+        for label in labels {
+            let item = gio::MenuItem::new(Some(&format!("Remove #{label}")), None);
+            item.set_action_and_target_value(
+                Some("selection.remove-label"),
+                Some(&label.to_variant()),
+            );
+            remove_section.append_item(&item);
+        }
+        label_menu.append_section(None, &remove_section);
+
+        let submenus = gio::Menu::new();
+        submenus.append_submenu(Some("_Due Date"), &due_menu("selection"));
+        submenus.append_submenu(Some("_Priority"), &priority_menu("selection"));
+        submenus.append_submenu(
+            Some("_Move to"),
+            &self.move_menu("selection", current_collection.as_ref()),
+        );
+        submenus.append_submenu(Some("_Labels"), &label_menu);
+        menu.append_section(None, &submenus);
+        menu
+    }
+
+    fn set_selected_due(&self, date: Option<NaiveDate>) {
+        for task_object in self.selected_tasks() {
+            set_due_date(&task_object, date);
+        }
+    }
+
+    async fn choose_selected_due(&self) {
+        if let Some(date) = self.choose_date(None).await {
+            self.set_selected_due(Some(date));
+        }
+    }
+
+    async fn add_label_to_selected(&self) {
+        let entry = Entry::builder()
+            .placeholder_text("Label")
+            .activates_default(true)
+            .build();
+        let cancel_response = "cancel";
+        let add_response = "add";
+
+        let dialog = AlertDialog::builder()
+            .heading("Add Label")
+            .close_response(cancel_response)
+            .default_response(add_response)
+            .extra_child(&entry)
+            .build();
+        dialog.add_responses(&[(cancel_response, "Cancel"), (add_response, "Add")]);
+        dialog.set_response_enabled(add_response, false);
+        dialog.set_response_appearance(add_response, ResponseAppearance::Suggested);
+        entry.connect_changed(clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                let label = entry.text();
+                let valid = !label.trim_start_matches('#').trim().is_empty()
+                    && !label.contains(char::is_whitespace);
+                dialog.set_response_enabled(add_response, valid);
+            }
+        ));
+
+        if dialog.choose_future(self).await != add_response {
+            return;
+        }
+        let text = entry.text();
+        let label = text.trim_start_matches('#');
+        for task_object in self.selected_tasks() {
+            task_object.add_label(label);
+        }
+    }
+
+    async fn delete_selected_tasks(&self) {
+        let tasks = self.selected_tasks();
+        if tasks.is_empty() {
+            return;
+        }
+        let cancel_response = "cancel";
+        let delete_response = "delete";
+
+        let dialog = AlertDialog::builder()
+            .heading(if tasks.len() == 1 {
+                String::from("Delete Task?")
+            } else {
+                format!("Delete {} Tasks?", tasks.len())
+            })
+            .close_response(cancel_response)
+            .default_response(cancel_response)
+            .build();
+        dialog
+            .add_responses(&[(cancel_response, "Cancel"), (delete_response, "Delete")]);
+        dialog
+            .set_response_appearance(delete_response, ResponseAppearance::Destructive);
+
+        if dialog.choose_future(self).await != delete_response {
+            return;
+        }
+        let collection = self.current_collection();
+        for task_object in &tasks {
+            collection.remove_task(task_object);
+        }
+        self.show_undo_toast(&if tasks.len() == 1 {
+            String::from("Task deleted")
+        } else {
+            format!("{} tasks deleted", tasks.len())
+        });
+    }
+}
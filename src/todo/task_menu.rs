@@ -0,0 +1,416 @@
+// Imports
+use super::RnTodo;
+use crate::collection_object::CollectionObject;
+use crate::quick_add;
+use crate::task_object::{TaskDue, TaskObject, MAX_PRIORITY};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{ActionRow, AlertDialog, ResponseAppearance};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use gtk::{gdk, gio, glib, glib::clone};
+
+/// Today, tomorrow, next week and no date for the `<group>.set-due` action, and
+/// `<group>.choose-due` for other dates.
+pub(super) fn due_menu(group: &str) -> gio::Menu {
+    let today = Local::now().date_naive();
+    let next_week =
+        today + Days::new(u64::from(7 - today.weekday().num_days_from_monday()));
+    let menu = gio::Menu::new();
+    for (label, date) in [
+        ("_Today", Some(today)),
+        ("To_morrow", Some(today + Days::new(1))),
+        ("_Next Week", Some(next_week)),
+        ("No _Date", None),
+    ] {
+        let target = date
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let item = gio::MenuItem::new(Some(label), None);
+        item.set_action_and_target_value(
+            Some(&format!("{group}.set-due")),
+            Some(&target.to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    menu.append(Some("_Choose Date…"), Some(&format!("{group}.choose-due")));
+    menu
+}
+
+/// The priorities for the `<group>.set-priority` action.
+pub(super) fn priority_menu(group: &str) -> gio::Menu {
+    let menu = gio::Menu::new();
+    for priority in 0..=MAX_PRIORITY {
+        let label = match priority {
+            0 => String::from("_None"),
+            priority => format!("Priority _{priority}"),
+        };
+        let item = gio::MenuItem::new(Some(&label), None);
+        item.set_action_and_target_value(
+            Some(&format!("{group}.set-priority")),
+            Some(&priority.to_variant()),
+        );
+        menu.append_item(&item);
+    }
+    menu
+}
+
+/// Parses the target of a `set-due` action, an empty string is no date.
+pub(super) fn parse_due_target(
+    parameter: Option<&glib::Variant>,
+) -> Option<Option<NaiveDate>> {
+    let target = parameter?.str()?;
+    Some(NaiveDate::parse_from_str(target, "%Y-%m-%d").ok())
+}
+
+/// Reschedules the task, keeping its time, or removes its due date.
+pub(super) fn set_due_date(task_object: &TaskObject, date: Option<NaiveDate>) {
+    match date {
+        Some(date) => task_object.reschedule(date),
+        None => task_object.set_due(None::<TaskDue>),
+    }
+}
+
+impl RnTodo {
+    /// The collections for the `<group>.move-to` action, except `skip`.
+    pub(super) fn move_menu(
+        &self,
+        group: &str,
+        skip: Option<&CollectionObject>,
+    ) -> gio::Menu {
+        let menu = gio::Menu::new();
+        for (position, collection) in self
+            .collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .enumerate()
+        {
+            if Some(&collection) == skip {
+                continue;
+            }
+            let item = gio::MenuItem::new(Some(&collection.title()), None);
+            item.set_action_and_target_value(
+                Some(&format!("{group}.move-to")),
+                Some(&(position as u32).to_variant()),
+            );
+            menu.append_item(&item);
+        }
+        menu
+    }
+
+    /// Asks for a date with a calendar, `None` when it is cancelled.
+    pub(super) async fn choose_date(
+        &self,
+        initial: Option<NaiveDate>,
+    ) -> Option<NaiveDate> {
+        let calendar = gtk::Calendar::new();
+        if let Some(date) = initial.and_then(|initial| {
+            glib::DateTime::from_local(
+                initial.year(),
+                initial.month() as i32,
+                initial.day() as i32,
+                0,
+                0,
+                0.0,
+            )
+            .ok()
+        }) {
+            calendar.select_day(&date);
+        }
+        let cancel_response = "cancel";
+        let set_response = "set";
+
+        let dialog = AlertDialog::builder()
+            .heading("Due Date")
+            .close_response(cancel_response)
+            .default_response(set_response)
+            .extra_child(&calendar)
+            .build();
+        dialog.add_responses(&[(cancel_response, "Cancel"), (set_response, "Set")]);
+        dialog.set_response_appearance(set_response, ResponseAppearance::Suggested);
+
+        if dialog.choose_future(self).await != set_response {
+            return None;
+        }
+        let date = calendar.date();
+        NaiveDate::from_ymd_opt(
+            date.year(),
+            date.month() as u32,
+            date.day_of_month() as u32,
+        )
+    }
+
+    /// The collection the task belongs to.
+    pub(crate) fn collection_of(
+        &self,
+        task_object: &TaskObject,
+    ) -> Option<CollectionObject> {
+        self.collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .find(|collection| collection.tasks().find(task_object).is_some())
+    }
+
+    /// The `task` actions of a row, they act on its task.
+    pub(super) fn task_actions(
+        &self,
+        task_object: &TaskObject,
+    ) -> gio::SimpleActionGroup {
+        let actions = gio::SimpleActionGroup::new();
+
+        let action_edit = gio::SimpleAction::new("edit", None);
+        action_edit.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, _| {
+                todo.open_task_detail(&task_object);
+            }
+        ));
+        actions.add_action(&action_edit);
+
+        let action_delete = gio::SimpleAction::new("delete", None);
+        action_delete.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, _| {
+                if let Some(collection) = todo.collection_of(&task_object) {
+                    collection.remove_task(&task_object);
+                    todo.show_undo_toast("Task deleted");
+                }
+                if todo.imp().task_detail.task().as_ref() == Some(&task_object) {
+                    todo.close_task_detail();
+                }
+            }
+        ));
+        actions.add_action(&action_delete);
+
+        let action_duplicate = gio::SimpleAction::new("duplicate", None);
+        action_duplicate.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, _| {
+                todo.duplicate_task(&task_object);
+            }
+        ));
+        actions.add_action(&action_duplicate);
+
+        let action_move_to =
+            gio::SimpleAction::new("move-to", Some(glib::VariantTy::UINT32));
+        action_move_to.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, parameter| {
+                if let Some(collection) = parameter
+                    .and_then(|parameter| parameter.get::<u32>())
+                    .and_then(|position| todo.collections().item(position))
+                    .and_downcast::<CollectionObject>()
+                {
+                    todo.move_task(&task_object, &collection);
+                }
+            }
+        ));
+        actions.add_action(&action_move_to);
+
+        let action_set_due =
+            gio::SimpleAction::new("set-due", Some(glib::VariantTy::STRING));
+        action_set_due.connect_activate(clone!(
+            #[weak]
+            task_object,
+            move |_, parameter| {
+                if let Some(date) = parse_due_target(parameter) {
+                    set_due_date(&task_object, date);
+                }
+            }
+        ));
+        actions.add_action(&action_set_due);
+
+        let action_choose_due = gio::SimpleAction::new("choose-due", None);
+        action_choose_due.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    todo,
+                    #[weak]
+                    task_object,
+                    async move {
+                        if let Some(date) =
+                            todo.choose_date(task_object.due_date()).await
+                        {
+                            set_due_date(&task_object, Some(date));
+                        }
+                    }
+                ));
+            }
+        ));
+        actions.add_action(&action_choose_due);
+
+        let action_set_priority =
+            gio::SimpleAction::new("set-priority", Some(glib::VariantTy::BYTE));
+        action_set_priority.connect_activate(clone!(
+            #[weak]
+            task_object,
+            move |_, parameter| {
+                if let Some(priority) =
+                    parameter.and_then(|parameter| parameter.get::<u8>())
+                {
+                    task_object.set_priority(priority);
+                }
+            }
+        ));
+        actions.add_action(&action_set_priority);
+
+        let action_copy = gio::SimpleAction::new("copy", None);
+        action_copy.connect_activate(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            task_object,
+            move |_, _| {
+                todo.clipboard()
+                    .set_text(&quick_add::task_text(&task_object));
+            }
+        ));
+        actions.add_action(&action_copy);
+
+        actions
+    }
+
+    /// The menu of the `task` actions.
+    fn task_menu(&self, task_object: &TaskObject) -> gio::Menu {
+        let menu = gio::Menu::new();
+
+        let section = gio::Menu::new();
+        section.append(Some("_Edit"), Some("task.edit"));
+        section.append(Some("D_uplicate"), Some("task.duplicate"));
+        section.append(Some("_Copy as Text"), Some("task.copy"));
+        menu.append_section(None, &section);
+
+        let section = gio::Menu::new();
+        section.append_submenu(Some("_Due Date"), &due_menu("task"));
+        section.append_submenu(Some("_Priority"), &priority_menu("task"));
+        section.append_submenu(
+            Some("_Move to"),
+            &self.move_menu("task", self.collection_of(task_object).as_ref()),
+        );
+        menu.append_section(None, &section);
+
+        let section = gio::Menu::new();
+        section.append(Some("_Delete"), Some("task.delete"));
+        menu.append_section(None, &section);
+        menu
+    }
+
+    /// Opens the menu of the `task` actions on right click, long press and the menu key.
+    pub(super) fn add_task_context_menu(
+        &self,
+        row: &ActionRow,
+        task_object: &TaskObject,
+    ) {
+        let click_gesture = gtk::GestureClick::builder()
+            .button(gdk::BUTTON_SECONDARY)
+            .build();
+        click_gesture.connect_pressed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            row,
+            #[weak]
+            task_object,
+            move |gesture, _, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                todo.show_task_menu(&row, &task_object, Some((x, y)));
+            }
+        ));
+        row.add_controller(click_gesture);
+
+        let long_press_gesture =
+            gtk::GestureLongPress::builder().touch_only(true).build();
+        long_press_gesture.connect_pressed(clone!(
+            #[weak(rename_to = todo)]
+            self,
+            #[weak]
+            row,
+            #[weak]
+            task_object,
+            move |gesture, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                todo.show_task_menu(&row, &task_object, Some((x, y)));
+            }
+        ));
+        row.add_controller(long_press_gesture);
+
+        let shortcuts = gtk::ShortcutController::new();
+        shortcuts.add_shortcut(gtk::Shortcut::new(
+            gtk::ShortcutTrigger::parse_string("Menu|<Shift>F10"),
+            Some(gtk::CallbackAction::new(clone!(
+                #[weak(rename_to = todo)]
+                self,
+                #[weak]
+                row,
+                #[weak]
+                task_object,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |_, _| {
+                    todo.show_task_menu(&row, &task_object, None);
+                    glib::Propagation::Stop
+                }
+            ))),
+        ));
+        row.add_controller(shortcuts);
+    }
+
+    /// Pops up the menu at the position in the row, or below it.
+    fn show_task_menu(
+        &self,
+        row: &ActionRow,
+        task_object: &TaskObject,
+        position: Option<(f64, f64)>,
+    ) {
+        let popover = gtk::PopoverMenu::builder()
+            .menu_model(&self.task_menu(task_object))
+            .has_arrow(position.is_none())
+            .build();
+        if let Some((x, y)) = position {
+            popover.set_halign(gtk::Align::Start);
+            popover
+                .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+        }
+        popover.set_parent(row);
+        // The menu is built again the next time, the actions of the row stay
+        popover.connect_closed(|popover| {
+            glib::idle_add_local_once(clone!(
+                #[weak]
+                popover,
+                move || {
+                    popover.unparent();
+                }
+            ));
+        });
+        popover.popup();
+    }
+
+    fn duplicate_task(&self, task_object: &TaskObject) {
+        let Some(collection) = self.collection_of(task_object) else {
+            return;
+        };
+        let mut task_data = task_object.task_data();
+        task_data.created_at = Some(Local::now());
+        let duplicate = TaskObject::from_task_data(task_data);
+
+        let tasks = collection.tasks();
+        let position = tasks.find(task_object).map_or(tasks.n_items(), |i| i + 1);
+        tasks.insert(position, &duplicate);
+    }
+}
@@ -0,0 +1,571 @@
+// Imports
+use super::RnTodo;
+use crate::collection_object::{CollectionData, CollectionObject};
+use crate::task_object::{RecurrenceUnit, TaskObject, TaskRecurrence};
+use crate::template::{TemplateData, TemplateSchedule};
+use crate::RnAppWindow;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{AlertDialog, ResponseAppearance};
+use chrono::{Datelike, Local, NaiveDate};
+use gtk::{gio, glib, glib::clone, Entry};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// The units of a schedule, in the order of the repeat row.
+const SCHEDULE_UNITS: [(RecurrenceUnit, &str); 4] = [
+    (RecurrenceUnit::Day, "Every Day"),
+    (RecurrenceUnit::Week, "Every Week"),
+    (RecurrenceUnit::Month, "Every Month"),
+    (RecurrenceUnit::Year, "Every Year"),
+];
+
+/// An entry row for each placeholder, in a boxed list.
+fn placeholder_rows(
+    placeholders: Vec<String>,
+) -> (gtk::ListBox, Vec<(String, adw::EntryRow)>) {
+    let list_box = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+    let rows = placeholders
+        .into_iter()
+        .map(|placeholder| {
+            let entry_row = adw::EntryRow::builder().title(&placeholder).build();
+            list_box.append(&entry_row);
+            (placeholder, entry_row)
+        })
+        .collect();
+    (list_box, rows)
+}
+
+fn placeholder_values(rows: &[(String, adw::EntryRow)]) -> HashMap<String, String> {
+    rows.iter()
+        .map(|(placeholder, entry_row)| {
+            (placeholder.clone(), entry_row.text().to_string())
+        })
+        .collect()
+}
+
+/// A calendar below a label, with the day selected.
+fn calendar_with_label(
+    label: &str,
+    day: NaiveDate,
+    content: &gtk::Box,
+) -> gtk::Calendar {
+    let calendar = gtk::Calendar::new();
+    if let Ok(date) = glib::DateTime::from_local(
+        day.year(),
+        day.month() as i32,
+        day.day() as i32,
+        0,
+        0,
+        0.0,
+    ) {
+        calendar.select_day(&date);
+    }
+    content.append(&gtk::Label::builder().label(label).xalign(0.0).build());
+    content.append(&calendar);
+    calendar
+}
+
+fn calendar_day(calendar: &gtk::Calendar) -> Option<NaiveDate> {
+    let date = calendar.date();
+    NaiveDate::from_ymd_opt(
+        date.year(),
+        date.month() as u32,
+        date.day_of_month() as u32,
+    )
+}
+
+impl RnTodo {
+    pub(super) fn restore_templates(&self, templates: Vec<TemplateData>) {
+        self.imp().templates.replace(templates);
+        self.update_templates_menu();
+    }
+
+    /// Lists the templates in the menu of the templates button, which is hidden while
+    /// there are none.
+    fn update_templates_menu(&self) {
+        let templates = self.imp().templates.borrow();
+        let menu = gio::Menu::new();
+        let schedule_menu = gio::Menu::new();
+        let delete_menu = gio::Menu::new();
+        for (index, template) in templates.iter().enumerate() {
+            let item = gio::MenuItem::new(Some(&template.name), None);
+            item.set_action_and_target_value(
+                Some("todo.new-from-template"),
+                Some(&(index as u32).to_variant()),
+            );
+            menu.append_item(&item);
+
+            let label = match &template.schedule {
+                Some(schedule) => {
+                    format!("{} ({})", template.name, schedule.recurrence.description())
+                }
+                None => template.name.clone(),
+            };
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(
+                Some("todo.schedule-template"),
+                Some(&(index as u32).to_variant()),
+            );
+            schedule_menu.append_item(&item);
+
+            let item = gio::MenuItem::new(Some(&template.name), None);
+            item.set_action_and_target_value(
+                Some("todo.delete-template"),
+                Some(&(index as u32).to_variant()),
+            );
+            delete_menu.append_item(&item);
+        }
+        let missed_runs_menu = gio::Menu::new();
+        for (label, limit) in [
+            ("Make Up for _All", 0u32),
+            ("Only the Last _3", 3),
+            ("Only the _Last", 1),
+        ] {
+            let item = gio::MenuItem::new(Some(label), None);
+            item.set_action_and_target_value(
+                Some("win.missed-runs-limit"),
+                Some(&limit.to_variant()),
+            );
+            missed_runs_menu.append_item(&item);
+        }
+        let section = gio::Menu::new();
+        section.append_submenu(Some("_Schedule"), &schedule_menu);
+        section.append_submenu(Some("_Missed Runs"), &missed_runs_menu);
+        section.append_submenu(Some("_Delete Template"), &delete_menu);
+        menu.append_section(None, &section);
+
+        let templates_button = self.imp().templates_button.get();
+        templates_button.set_menu_model(Some(&menu));
+        templates_button.set_visible(!templates.is_empty());
+    }
+
+    fn templates_changed(&self) {
+        self.update_templates_menu();
+        self.queue_tasks_changed();
+    }
+
+    /// Asks for a name and saves the collection as template, replacing the template
+    /// with the same name.
+    pub(super) async fn save_as_template(&self, collection: &CollectionObject) {
+        let entry = Entry::builder()
+            .placeholder_text("Name")
+            .text(collection.title())
+            .activates_default(true)
+            .build();
+
+        let cancel_response = "cancel";
+        let save_response = "save";
+
+        let dialog = AlertDialog::builder()
+            .heading("Save as Template")
+            .body("Write placeholders like {client} in titles to fill them in when the template is used.")
+            .close_response(cancel_response)
+            .default_response(save_response)
+            .extra_child(&entry)
+            .build();
+        dialog.add_responses(&[(cancel_response, "Cancel"), (save_response, "Save")]);
+        dialog.set_response_appearance(save_response, ResponseAppearance::Suggested);
+
+        // The name can't be empty
+        entry.connect_changed(clone!(
+            #[weak]
+            dialog,
+            move |entry| {
+                let empty = entry.text().is_empty();
+                dialog.set_response_enabled(save_response, !empty);
+                if empty {
+                    entry.add_css_class("error");
+                } else {
+                    entry.remove_css_class("error");
+                }
+            }
+        ));
+
+        if dialog.choose_future(self).await != save_response {
+            return;
+        }
+        let template = TemplateData::from_collection(
+            &entry.text(),
+            collection.to_collection_data(),
+        );
+        {
+            let mut templates = self.imp().templates.borrow_mut();
+            match templates
+                .iter_mut()
+                .find(|saved| saved.name == template.name)
+            {
+                Some(saved) => *saved = template,
+                None => templates.push(template),
+            }
+        }
+        self.templates_changed();
+    }
+
+    /// Asks for the placeholders and the start date, and adds a collection from the
+    /// template.
+    pub(super) async fn new_from_template(&self, index: usize) {
+        let Some(template) = self.imp().templates.borrow().get(index).cloned() else {
+            return;
+        };
+        if self.is_locked() {
+            return;
+        }
+
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+        let today = Local::now().date_naive();
+        let (list_box, rows) = placeholder_rows(template.placeholders());
+        for (placeholder, entry_row) in &rows {
+            if placeholder == "date" {
+                entry_row.set_text(&today.format("%Y-%m-%d").to_string());
+            }
+        }
+        if !rows.is_empty() {
+            content.append(&list_box);
+        }
+        // The due dates start from the chosen day
+        let calendar = calendar_with_label("Start Date", today, &content);
+
+        let cancel_response = "cancel";
+        let create_response = "create";
+
+        let dialog = AlertDialog::builder()
+            .heading(format!("New from “{}”", template.name))
+            .close_response(cancel_response)
+            .default_response(create_response)
+            .extra_child(&content)
+            .build();
+        dialog
+            .add_responses(&[(cancel_response, "Cancel"), (create_response, "Create")]);
+        dialog.set_response_appearance(create_response, ResponseAppearance::Suggested);
+
+        if dialog.choose_future(self).await != create_response {
+            return;
+        }
+        let anchor = calendar_day(&calendar).unwrap_or(today);
+        let collection = CollectionObject::from_collection_data(
+            template.instantiate(&placeholder_values(&rows), anchor),
+        );
+        self.collections().append(&collection);
+        self.show_collection(&collection);
+        self.select_collection_row();
+    }
+
+    pub(super) async fn delete_template(&self, index: usize) {
+        let Some(name) = self
+            .imp()
+            .templates
+            .borrow()
+            .get(index)
+            .map(|template| template.name.clone())
+        else {
+            return;
+        };
+        let cancel_response = "cancel";
+        let delete_response = "delete";
+
+        let dialog = AlertDialog::builder()
+            .heading(format!("Delete Template “{name}”?"))
+            .body("Collections created from it are kept.")
+            .close_response(cancel_response)
+            .default_response(cancel_response)
+            .build();
+        dialog
+            .add_responses(&[(cancel_response, "Cancel"), (delete_response, "Delete")]);
+        dialog
+            .set_response_appearance(delete_response, ResponseAppearance::Destructive);
+
+        if dialog.choose_future(self).await != delete_response {
+            return;
+        }
+        self.imp()
+            .templates
+            .borrow_mut()
+            .retain(|template| template.name != name);
+        self.templates_changed();
+    }
+
+    /// Asks when and how the template is created on its own, or stops its schedule.
+    pub(super) async fn schedule_template(&self, index: usize) {
+        let Some(template) = self.imp().templates.borrow().get(index).cloned() else {
+            return;
+        };
+        let schedule = template.schedule.clone();
+        let today = Local::now().date_naive();
+
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(12)
+            .build();
+        // `{date}` is the day of each run
+        let (list_box, rows) = placeholder_rows(
+            template
+                .placeholders()
+                .into_iter()
+                .filter(|placeholder| placeholder != "date")
+                .collect(),
+        );
+        for (placeholder, entry_row) in &rows {
+            if let Some(value) = schedule
+                .as_ref()
+                .and_then(|schedule| schedule.values.get(placeholder))
+            {
+                entry_row.set_text(value);
+            }
+        }
+        if !rows.is_empty() {
+            content.append(&list_box);
+        }
+
+        let title_row = adw::EntryRow::builder()
+            .title("Title")
+            .text(schedule.as_ref().map_or_else(
+                || format!("{} {{date}}", template.collection.title),
+                |schedule| schedule.title.clone(),
+            ))
+            .build();
+        let repeat_row = adw::ComboRow::builder()
+            .title("Repeats")
+            .model(&gtk::StringList::new(
+                &SCHEDULE_UNITS.map(|(_, label)| label),
+            ))
+            .build();
+        repeat_row.set_selected(
+            schedule
+                .as_ref()
+                .and_then(|schedule| {
+                    SCHEDULE_UNITS
+                        .iter()
+                        .position(|(unit, _)| *unit == schedule.recurrence.unit)
+                })
+                .unwrap_or(1) as u32,
+        );
+        // A new collection, or a new section in one of the collections
+        let collection_titles: Vec<String> = self
+            .collections()
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .map(|collection| collection.title())
+            .collect();
+        let mut target_labels = vec![String::from("New Collection")];
+        target_labels.extend(
+            collection_titles
+                .iter()
+                .map(|title| format!("Section in {title}")),
+        );
+        let target_row = adw::ComboRow::builder()
+            .title("Creates")
+            .subtitle(
+                "A section keeps the sections of the template as “Title / Section”",
+            )
+            .model(&gtk::StringList::new(
+                &target_labels.iter().map(String::as_str).collect::<Vec<_>>(),
+            ))
+            .build();
+        if let Some(position) = schedule
+            .as_ref()
+            .and_then(|schedule| schedule.section_of.as_ref())
+            .and_then(|section_of| {
+                collection_titles
+                    .iter()
+                    .position(|title| title == section_of)
+            })
+        {
+            target_row.set_selected(position as u32 + 1);
+        }
+        let options_list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .css_classes(["boxed-list"])
+            .build();
+        options_list.append(&title_row);
+        options_list.append(&repeat_row);
+        options_list.append(&target_row);
+        content.append(&options_list);
+
+        let calendar = calendar_with_label(
+            "First Run",
+            schedule
+                .as_ref()
+                .map_or(today, |schedule| schedule.next_run),
+            &content,
+        );
+
+        let cancel_response = "cancel";
+        let stop_response = "stop";
+        let schedule_response = "schedule";
+
+        let dialog = AlertDialog::builder()
+            .heading(format!("Schedule “{}”", template.name))
+            .close_response(cancel_response)
+            .default_response(schedule_response)
+            .extra_child(&content)
+            .build();
+        dialog.add_response(cancel_response, "Cancel");
+        if schedule.is_some() {
+            dialog.add_response(stop_response, "Stop");
+            dialog.set_response_appearance(
+                stop_response,
+                ResponseAppearance::Destructive,
+            );
+        }
+        dialog.add_response(schedule_response, "Schedule");
+        dialog
+            .set_response_appearance(schedule_response, ResponseAppearance::Suggested);
+
+        let response = dialog.choose_future(self).await;
+        let schedule = if response == schedule_response {
+            let unit = SCHEDULE_UNITS
+                .get(repeat_row.selected() as usize)
+                .map_or(RecurrenceUnit::Week, |(unit, _)| *unit);
+            let first_run = calendar_day(&calendar).unwrap_or(today);
+            Some(TemplateSchedule {
+                recurrence: TaskRecurrence::new(1, unit),
+                next_run: first_run,
+                start: Some(first_run),
+                runs: 0,
+                title: title_row.text().to_string(),
+                values: placeholder_values(&rows),
+                section_of: (target_row.selected() as usize)
+                    .checked_sub(1)
+                    .and_then(|position| collection_titles.get(position))
+                    .cloned(),
+            })
+        } else if response == stop_response {
+            None
+        } else {
+            return;
+        };
+        {
+            let mut templates = self.imp().templates.borrow_mut();
+            let Some(template) = templates
+                .iter_mut()
+                .find(|saved| saved.name == template.name)
+            else {
+                return;
+            };
+            template.schedule = schedule;
+        }
+        self.templates_changed();
+        self.run_scheduled_templates();
+    }
+
+    /// Checks for runs of scheduled templates once a minute.
+    pub(super) fn setup_template_schedules(&self) {
+        glib::timeout_add_seconds_local(
+            60,
+            clone!(
+                #[weak(rename_to = todo)]
+                self,
+                #[upgrade_or]
+                glib::ControlFlow::Break,
+                move || {
+                    if !todo.is_locked() {
+                        todo.run_scheduled_templates();
+                    }
+                    glib::ControlFlow::Continue
+                }
+            ),
+        );
+    }
+
+    /// Creates the collections or sections of the scheduled templates that are due,
+    /// including the runs that were missed while the app was closed.
+    ///
+    /// The `missed-runs-limit` setting can limit the missed runs to the last ones.
+    pub(super) fn run_scheduled_templates(&self) {
+        let today = Local::now().date_naive();
+        let limit = self
+            .root()
+            .and_downcast::<RnAppWindow>()
+            .and_then(|appwindow| appwindow.app().app_settings())
+            .map_or(0, |app_settings| {
+                app_settings.uint("missed-runs-limit") as usize
+            });
+        let mut runs = Vec::new();
+        let mut skipped = 0;
+        for template in self.imp().templates.borrow_mut().iter_mut() {
+            let section_of = template
+                .schedule
+                .as_ref()
+                .and_then(|schedule| schedule.section_of.clone());
+            let (template_runs, template_skipped) = template.due_runs(today, limit);
+            runs.extend(
+                template_runs
+                    .into_iter()
+                    .map(|collection_data| (collection_data, section_of.clone())),
+            );
+            skipped += template_skipped;
+        }
+        if runs.is_empty() {
+            return;
+        }
+        for (collection_data, section_of) in runs {
+            self.add_scheduled_run(collection_data, section_of.as_deref());
+        }
+        self.templates_changed();
+        if skipped > 0 {
+            self.show_toast(&if skipped == 1 {
+                String::from("1 missed scheduled run skipped")
+            } else {
+                format!("{skipped} missed scheduled runs skipped")
+            });
+        }
+    }
+
+    /// Adds the run as new collection, or as new section of the collection with the
+    /// title `section_of`.
+    ///
+    /// The sections of the template become `Title / Section` sections below the new
+    /// one. A run whose collection doesn't exist anymore is added as collection.
+    fn add_scheduled_run(
+        &self,
+        collection_data: CollectionData,
+        section_of: Option<&str>,
+    ) {
+        let collection = section_of.and_then(|title| {
+            self.collections()
+                .iter::<CollectionObject>()
+                .filter_map(Result::ok)
+                .find(|collection| collection.title() == title)
+        });
+        let Some(collection) = collection else {
+            if let Some(title) = section_of {
+                warn!("The collection “{title}” of a scheduled template is missing");
+                self.show_toast(&format!(
+                    "“{title}” is missing, “{}” was added as collection",
+                    collection_data.title
+                ));
+            }
+            self.collections()
+                .append(&CollectionObject::from_collection_data(collection_data));
+            return;
+        };
+        let title = collection_data.title;
+        let section_of_task = |section: &str| {
+            if section.is_empty() {
+                title.clone()
+            } else {
+                format!("{title} / {section}")
+            }
+        };
+        collection.add_section(&title);
+        for section in &collection_data.sections {
+            collection.add_section(&section_of_task(section));
+        }
+        let tasks: Vec<TaskObject> = collection_data
+            .tasks_data
+            .into_iter()
+            .map(|mut task_data| {
+                task_data.section = section_of_task(&task_data.section);
+                TaskObject::from_task_data(task_data)
+            })
+            .collect();
+        collection.tasks().extend_from_slice(&tasks);
+    }
+}
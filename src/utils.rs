@@ -1,6 +1,7 @@
 // ANCHOR_END: data_path
 // Imports
 use crate::config::APP_ID;
+use crate::env;
 use anyhow::Context;
 use futures::AsyncWriteExt;
 use gtk::glib;
@@ -13,12 +14,47 @@ use std::slice::Iter;
 // ANCHOR: data_path
 #[allow(unused)]
 pub fn data_path() -> PathBuf {
-    let mut path = glib::user_data_dir();
-    path.push(APP_ID);
+    let mut path = env::portable_dir().unwrap_or_else(|| {
+        let mut path = glib::user_data_dir();
+        path.push(APP_ID);
+        path
+    });
     std::fs::create_dir_all(&path).expect("Could not create directory.");
     path.push("data.json");
     path
 }
+
+/// The backup of the data file, holding the state before the last save.
+pub fn backup_path() -> PathBuf {
+    let mut path = data_path();
+    path.set_extension("json.bak");
+    path
+}
+/// Writes the data file, keeping its previous state as backup.
+///
+/// The new data is written to a temporary file first, so that the data file is
+/// complete at any time.
+pub fn write_data_file(bytes: &[u8]) -> anyhow::Result<()> {
+    let path = data_path();
+    let mut tmp_path = path.clone();
+    tmp_path.set_extension("json.tmp");
+
+    let mut tmp_file = std::fs::File::create(&tmp_path)
+        .context("Failed to create temporary data file")?;
+    std::io::Write::write_all(&mut tmp_file, bytes)
+        .context("Failed to write temporary data file")?;
+    tmp_file
+        .sync_all()
+        .context("Failed to sync temporary data file")?;
+    drop(tmp_file);
+
+    if path.exists() {
+        std::fs::copy(&path, backup_path())
+            .context("Failed to copy data file to backup")?;
+    }
+    std::fs::rename(&tmp_path, &path).context("Failed to replace data file")?;
+    Ok(())
+}
 #[allow(unused)]
 /// Create a new file or replace if it already exists, asynchronously.
 pub(crate) async fn create_replace_file_future(
@@ -0,0 +1,478 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::{TaskDue, TaskObject};
+use crate::views::{add_task_drag_source, add_task_drop_target, sort_by_due};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use gtk::{
+    glib, glib::clone, Button, CompositeTemplate, Entry, Grid, Label, Popover,
+    ToggleButton, Widget,
+};
+use std::cell::Cell;
+
+/// How many tasks a day of the month layout lists before summarizing the rest.
+const MONTH_TASKS_SHOWN: usize = 3;
+
+/// Whether the calendar shows a whole month or a single week.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum CalendarLayout {
+    #[default]
+    Month,
+    Week,
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/calendar.ui")]
+    pub(crate) struct RnCalendarView {
+        pub(crate) appwindow: glib::WeakRef<RnAppWindow>,
+        /// A day of the shown month or week
+        pub(crate) anchor: Cell<NaiveDate>,
+        pub(crate) layout: Cell<CalendarLayout>,
+
+        #[template_child]
+        pub(crate) title_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) prev_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) today_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) next_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) month_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) week_button: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) weekdays_grid: TemplateChild<Grid>,
+        #[template_child]
+        pub(crate) days_grid: TemplateChild<Grid>,
+    }
+
+    impl Default for RnCalendarView {
+        fn default() -> Self {
+            Self {
+                appwindow: glib::WeakRef::default(),
+                anchor: Cell::new(Local::now().date_naive()),
+                layout: Cell::new(CalendarLayout::default()),
+
+                title_label: TemplateChild::<Label>::default(),
+                prev_button: TemplateChild::<Button>::default(),
+                today_button: TemplateChild::<Button>::default(),
+                next_button: TemplateChild::<Button>::default(),
+                month_button: TemplateChild::<ToggleButton>::default(),
+                week_button: TemplateChild::<ToggleButton>::default(),
+                weekdays_grid: TemplateChild::<Grid>::default(),
+                days_grid: TemplateChild::<Grid>::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnCalendarView {
+        const NAME: &'static str = "RnCalendarView";
+        type Type = super::RnCalendarView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnCalendarView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnCalendarView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnCalendarView(ObjectSubclass<imp::RnCalendarView>)
+        @extends Widget;
+}
+
+impl Default for RnCalendarView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnCalendarView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn appwindow(&self) -> RnAppWindow {
+        self.imp()
+            .appwindow
+            .upgrade()
+            .expect("`appwindow` should be set in `init`.")
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.appwindow().todo()
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        imp.appwindow.set(Some(appwindow));
+
+        appwindow.todo().connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        // The day might have changed since the last refresh
+        self.connect_map(|view| {
+            view.refresh();
+        });
+        imp.prev_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.step(false);
+            }
+        ));
+        imp.next_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.step(true);
+            }
+        ));
+        imp.today_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.imp().anchor.set(Local::now().date_naive());
+                view.refresh();
+            }
+        ));
+        imp.week_button.connect_toggled(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |week_button| {
+                view.imp().layout.set(if week_button.is_active() {
+                    CalendarLayout::Week
+                } else {
+                    CalendarLayout::Month
+                });
+                view.refresh();
+            }
+        ));
+
+        self.setup_weekdays();
+    }
+
+    fn setup_weekdays(&self) {
+        let mut weekday = first_weekday();
+        for column in 0..7 {
+            let label = Label::new(Some(&weekday_abbreviation(weekday)));
+            label.add_css_class("caption-heading");
+            label.add_css_class("dim-label");
+            self.imp().weekdays_grid.attach(&label, column, 0, 1, 1);
+            weekday = weekday.succ();
+        }
+    }
+
+    /// Moves to the next or the previous month or week.
+    fn step(&self, forward: bool) {
+        let imp = self.imp();
+        let anchor = imp.anchor.get();
+        let anchor = match (imp.layout.get(), forward) {
+            (CalendarLayout::Month, true) => anchor + Months::new(1),
+            (CalendarLayout::Month, false) => anchor - Months::new(1),
+            (CalendarLayout::Week, true) => anchor + Days::new(7),
+            (CalendarLayout::Week, false) => anchor - Days::new(7),
+        };
+        imp.anchor.set(anchor);
+        self.refresh();
+    }
+
+    /// The shown days, in rows of weeks.
+    fn shown_weeks(&self) -> Vec<[NaiveDate; 7]> {
+        let imp = self.imp();
+        let anchor = imp.anchor.get();
+        let first_weekday = first_weekday();
+        let week_start = |date: NaiveDate| {
+            let days_since = (7 + date.weekday().num_days_from_monday()
+                - first_weekday.num_days_from_monday())
+                % 7;
+            date - Days::new(u64::from(days_since))
+        };
+        let week =
+            |start: NaiveDate| std::array::from_fn(|i| start + Days::new(i as u64));
+
+        match imp.layout.get() {
+            CalendarLayout::Week => vec![week(week_start(anchor))],
+            CalendarLayout::Month => {
+                let first = anchor
+                    .with_day(1)
+                    .expect("the first day exists in every month");
+                let last = first + Months::new(1) - Days::new(1);
+                let mut start = week_start(first);
+                let mut weeks = Vec::new();
+                while start <= last {
+                    weeks.push(week(start));
+                    start = start + Days::new(7);
+                }
+                weeks
+            }
+        }
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let today = Local::now().date_naive();
+        let anchor = imp.anchor.get();
+        let layout = imp.layout.get();
+        let weeks = self.shown_weeks();
+
+        imp.title_label.set_label(&match layout {
+            CalendarLayout::Month => anchor.format("%B %Y").to_string(),
+            CalendarLayout::Week => {
+                let first = weeks[0][0];
+                let last = weeks[0][6];
+                if first.month() == last.month() {
+                    format!("{} – {}", first.format("%B %-d"), last.format("%-d, %Y"))
+                } else {
+                    format!(
+                        "{} – {}",
+                        first.format("%b %-d"),
+                        last.format("%b %-d, %Y")
+                    )
+                }
+            }
+        });
+
+        while let Some(child) = imp.days_grid.first_child() {
+            imp.days_grid.remove(&child);
+        }
+
+        let mut dated_tasks: Vec<(CollectionObject, TaskObject)> = self
+            .todo()
+            .all_tasks()
+            .into_iter()
+            .filter(|(_, task_object)| {
+                !task_object.is_completed() && task_object.due().is_some()
+            })
+            .collect();
+        sort_by_due(&mut dated_tasks);
+
+        for (row, week) in weeks.iter().enumerate() {
+            for (column, date) in week.iter().enumerate() {
+                let tasks: Vec<&TaskObject> = dated_tasks
+                    .iter()
+                    .filter(|(_, task_object)| task_object.due_date() == Some(*date))
+                    .map(|(_, task_object)| task_object)
+                    .collect();
+                let outside_month =
+                    layout == CalendarLayout::Month && date.month() != anchor.month();
+                let cell = self.create_day(*date, today, outside_month, &tasks);
+                imp.days_grid.attach(&cell, column as i32, row as i32, 1, 1);
+            }
+        }
+    }
+
+    fn create_day(
+        &self,
+        date: NaiveDate,
+        today: NaiveDate,
+        outside_month: bool,
+        tasks: &[&TaskObject],
+    ) -> gtk::Box {
+        let layout = self.imp().layout.get();
+        let cell = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(2)
+            .vexpand(true)
+            .build();
+        cell.add_css_class("calendar_day");
+        if date == today {
+            cell.add_css_class("today");
+        }
+        if outside_month {
+            cell.add_css_class("dim-label");
+        }
+
+        let day_label = Label::builder()
+            .label(match layout {
+                CalendarLayout::Month => date.day().to_string(),
+                CalendarLayout::Week => date.format("%-d %b").to_string(),
+            })
+            .xalign(0.0)
+            .build();
+        day_label.add_css_class("caption-heading");
+        cell.append(&day_label);
+
+        let shown = match layout {
+            CalendarLayout::Month => MONTH_TASKS_SHOWN,
+            CalendarLayout::Week => tasks.len(),
+        };
+        for task_object in tasks.iter().take(shown) {
+            cell.append(&self.create_task_chip(task_object));
+        }
+        if tasks.len() > shown {
+            let more = Label::builder()
+                .label(format!("{} more", tasks.len() - shown))
+                .xalign(0.0)
+                .build();
+            more.add_css_class("caption");
+            more.add_css_class("dim-label");
+            cell.append(&more);
+        }
+
+        add_task_drop_target(&cell, move |task_object| {
+            task_object.reschedule(date);
+        });
+
+        // Clicking the free space of a day creates a task on it
+        let click = gtk::GestureClick::new();
+        click.connect_released(clone!(
+            #[weak(rename_to = view)]
+            self,
+            #[weak]
+            cell,
+            move |_, _, _, _| {
+                view.show_new_task_popover(&cell, date);
+            }
+        ));
+        cell.add_controller(click);
+        cell
+    }
+
+    fn create_task_chip(&self, task_object: &TaskObject) -> Button {
+        let mut text = task_object.content();
+        if let Some(time) = task_object.due().and_then(|due| due.time) {
+            text = format!("{} {text}", time.format("%H:%M"));
+        }
+        let label = Label::builder()
+            .label(text)
+            .xalign(0.0)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build();
+        let chip = Button::builder()
+            .child(&label)
+            .tooltip_text(task_object.content())
+            .build();
+        chip.add_css_class("flat");
+        chip.add_css_class("calendar_task");
+        chip.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            #[weak]
+            task_object,
+            move |_| {
+                view.open_task(&task_object);
+            }
+        ));
+        add_task_drag_source(&chip, task_object);
+        chip
+    }
+
+    /// Shows the task in its collection.
+    fn open_task(&self, task_object: &TaskObject) {
+        let appwindow = self.appwindow();
+        let todo = appwindow.todo();
+        let Some((collection, _)) = todo
+            .all_tasks()
+            .into_iter()
+            .find(|(_, shown_task)| shown_task == task_object)
+        else {
+            return;
+        };
+        appwindow.views_stack().set_visible_child_name("done_page");
+        todo.show_task(&collection, task_object);
+    }
+
+    /// Asks for the content of a task that is due on the date and adds it to the Inbox.
+    fn show_new_task_popover(&self, cell: &gtk::Box, date: NaiveDate) {
+        let entry = Entry::builder()
+            .placeholder_text(format!("New Task on {}", date.format("%b %-d")))
+            .secondary_icon_name("list-add-symbolic")
+            .build();
+        let popover = Popover::builder().child(&entry).build();
+        popover.set_parent(cell);
+        popover.connect_closed(|popover| {
+            popover.unparent();
+        });
+
+        let add_task = clone!(
+            #[weak(rename_to = view)]
+            self,
+            #[weak]
+            popover,
+            move |entry: &Entry| {
+                let content = entry.text().to_string();
+                if content.is_empty() {
+                    return;
+                }
+                let task_object = TaskObject::new(false, content);
+                task_object.set_due(Some(TaskDue::new(date, None)));
+                view.todo().add_task(&task_object, None);
+                popover.popdown();
+            }
+        );
+        entry.connect_activate(add_task.clone());
+        entry.connect_icon_release(move |entry, _| add_task(entry));
+        popover.popup();
+    }
+}
+
+/// The first day of the week in the locale of dates and times.
+///
+/// Most regions start the week on Monday, the exceptions are taken from the Unicode CLDR.
+fn first_weekday() -> Weekday {
+    const SUNDAY_REGIONS: &[&str] = &[
+        "AG", "AS", "BD", "BR", "BS", "BT", "BW", "BZ", "CA", "CN", "CO", "DM", "DO",
+        "ET", "GT", "GU", "HK", "HN", "ID", "IL", "IN", "JM", "JP", "KE", "KH", "KR",
+        "LA", "MH", "MM", "MO", "MT", "MX", "MZ", "NI", "NP", "PA", "PE", "PH", "PK",
+        "PR", "PT", "PY", "SA", "SG", "SV", "TH", "TT", "TW", "UM", "US", "VE", "VI",
+        "WS", "YE", "ZA", "ZW",
+    ];
+    const SATURDAY_REGIONS: &[&str] = &[
+        "AE", "AF", "BH", "DJ", "DZ", "EG", "IQ", "IR", "JO", "KW", "LY", "OM", "QA",
+        "SD", "SY",
+    ];
+
+    // Locale names look like `language_REGION.codeset@modifier`
+    let region = glib::language_names_with_category("LC_TIME")
+        .into_iter()
+        .find_map(|name| {
+            let name = name.split(['.', '@']).next()?;
+            let (_, region) = name.split_once('_')?;
+            Some(region.to_uppercase())
+        });
+    match region.as_deref() {
+        Some(region) if SUNDAY_REGIONS.contains(&region) => Weekday::Sun,
+        Some(region) if SATURDAY_REGIONS.contains(&region) => Weekday::Sat,
+        _ => Weekday::Mon,
+    }
+}
+
+fn weekday_abbreviation(weekday: Weekday) -> String {
+    // Any date works, only its weekday is formatted
+    let monday = NaiveDate::from_isoywd_opt(2024, 1, Weekday::Mon)
+        .expect("the first week of 2024 has a Monday");
+    let date = monday + Days::new(u64::from(weekday.num_days_from_monday()));
+    date.format("%a").to_string()
+}
@@ -0,0 +1,292 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::{Datelike, Days, Local, NaiveDate};
+use gtk::{
+    glib, glib::clone, Button, CompositeTemplate, DropDown, Label, ListBox,
+    SearchEntry, Stack, StringList, Widget,
+};
+use std::cmp::Reverse;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/completed.ui")]
+    pub(crate) struct RnCompletedView {
+        pub(crate) todo: glib::WeakRef<RnTodo>,
+
+        #[template_child]
+        pub(crate) search_entry: TemplateChild<SearchEntry>,
+        #[template_child]
+        pub(crate) collection_dropdown: TemplateChild<DropDown>,
+        #[template_child]
+        pub(crate) stack: TemplateChild<Stack>,
+        #[template_child]
+        pub(crate) groups_box: TemplateChild<gtk::Box>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnCompletedView {
+        const NAME: &'static str = "RnCompletedView";
+        type Type = super::RnCompletedView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnCompletedView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnCompletedView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnCompletedView(ObjectSubclass<imp::RnCompletedView>)
+        @extends Widget;
+}
+
+impl Default for RnCompletedView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnCompletedView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.imp()
+            .todo
+            .upgrade()
+            .expect("`todo` should be set in `init`.")
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let todo = appwindow.todo();
+        imp.todo.set(Some(&todo));
+
+        todo.connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.update_collection_filter();
+                    view.refresh();
+                }
+            }
+        ));
+        // The day might have changed since the last refresh
+        self.connect_map(|view| {
+            view.update_collection_filter();
+            view.refresh();
+        });
+        imp.search_entry.connect_search_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.refresh();
+            }
+        ));
+        imp.collection_dropdown.connect_selected_notify(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.refresh();
+            }
+        ));
+    }
+
+    /// Lists "All Collections" and the title of every collection, keeping the selection.
+    fn update_collection_filter(&self) {
+        let dropdown = self.imp().collection_dropdown.get();
+        let mut titles = vec![String::from("All Collections")];
+        titles.extend(
+            self.todo()
+                .collections()
+                .iter::<CollectionObject>()
+                .filter_map(Result::ok)
+                .map(|collection| collection.title()),
+        );
+
+        let current = dropdown.model().and_downcast::<StringList>();
+        let unchanged = current.as_ref().is_some_and(|model| {
+            model.n_items() as usize == titles.len()
+                && titles
+                    .iter()
+                    .enumerate()
+                    .all(|(i, title)| model.string(i as u32).as_deref() == Some(title))
+        });
+        if unchanged {
+            return;
+        }
+
+        let selected_title = self.selected_collection_title();
+        let model =
+            StringList::new(&titles.iter().map(String::as_str).collect::<Vec<_>>());
+        dropdown.set_model(Some(&model));
+        let selected = selected_title
+            .and_then(|title| titles.iter().skip(1).position(|t| *t == title))
+            .map_or(0, |i| i + 1);
+        dropdown.set_selected(selected as u32);
+    }
+
+    /// The title of the collection to filter by, `None` for all collections.
+    fn selected_collection_title(&self) -> Option<String> {
+        let dropdown = self.imp().collection_dropdown.get();
+        if dropdown.selected() == 0 {
+            return None;
+        }
+        dropdown
+            .selected_item()
+            .and_downcast::<gtk::StringObject>()
+            .map(|item| item.string().to_string())
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let todo = self.todo();
+        let search = imp.search_entry.text().to_lowercase();
+        let collection_title = self.selected_collection_title();
+
+        while let Some(child) = imp.groups_box.first_child() {
+            imp.groups_box.remove(&child);
+        }
+
+        let mut completed: Vec<(CollectionObject, TaskObject)> = todo
+            .all_tasks()
+            .into_iter()
+            .filter(|(collection, task_object)| {
+                task_object.is_completed()
+                    && collection_title
+                        .as_ref()
+                        .is_none_or(|title| collection.title() == *title)
+                    && (search.is_empty()
+                        || task_object.content().to_lowercase().contains(&search)
+                        || collection.title().to_lowercase().contains(&search))
+            })
+            .collect();
+        // Most recently completed first, tasks without a timestamp last
+        completed.sort_by_key(|(_, task_object)| {
+            let completed_at = task_object.completed_at();
+            (completed_at.is_none(), Reverse(completed_at))
+        });
+
+        let today = Local::now().date_naive();
+        let mut chunk_start = 0;
+        while chunk_start < completed.len() {
+            let day = completion_day(&completed[chunk_start].1);
+            let chunk_len = completed[chunk_start..]
+                .iter()
+                .take_while(|(_, task_object)| completion_day(task_object) == day)
+                .count();
+            let chunk = &completed[chunk_start..chunk_start + chunk_len];
+            self.append_group(&todo, &day_title(day, today), chunk);
+            chunk_start += chunk_len;
+        }
+
+        imp.stack.set_visible_child_name(if completed.is_empty() {
+            "empty"
+        } else {
+            "tasks"
+        });
+    }
+
+    fn append_group(
+        &self,
+        todo: &RnTodo,
+        title: &str,
+        tasks: &[(CollectionObject, TaskObject)],
+    ) {
+        let imp = self.imp();
+
+        let header = Label::builder().label(title).xalign(0.0).build();
+        header.add_css_class("heading");
+        imp.groups_box.append(&header);
+
+        let list = ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        list.add_css_class("boxed-list");
+        for (collection, task_object) in tasks {
+            let row = todo.create_task_row(task_object);
+            let mut subtitle = collection.title();
+            if let Some(completed_at) = task_object.completed_at() {
+                subtitle += &format!(" · {}", completed_at.format("%H:%M"));
+            }
+            row.set_subtitle(&subtitle);
+
+            let uncomplete_button = Button::builder()
+                .icon_name("edit-undo-symbolic")
+                .tooltip_text("Mark as Not Completed")
+                .valign(gtk::Align::Center)
+                .build();
+            uncomplete_button.add_css_class("flat");
+            uncomplete_button.connect_clicked(clone!(
+                #[weak]
+                task_object,
+                move |_| {
+                    task_object.set_completed(false);
+                }
+            ));
+            row.add_suffix(&uncomplete_button);
+
+            let delete_button = Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text("Delete")
+                .valign(gtk::Align::Center)
+                .build();
+            delete_button.add_css_class("flat");
+            delete_button.connect_clicked(clone!(
+                #[weak]
+                collection,
+                #[weak]
+                task_object,
+                move |_| {
+                    collection.remove_task(&task_object);
+                }
+            ));
+            row.add_suffix(&delete_button);
+
+            list.append(&row);
+        }
+        imp.groups_box.append(&list);
+    }
+}
+
+fn completion_day(task_object: &TaskObject) -> Option<NaiveDate> {
+    task_object
+        .completed_at()
+        .map(|completed_at| completed_at.date_naive())
+}
+
+fn day_title(day: Option<NaiveDate>, today: NaiveDate) -> String {
+    match day {
+        None => String::from("Earlier"),
+        Some(day) if day == today => String::from("Today"),
+        Some(day) if day + Days::new(1) == today => String::from("Yesterday"),
+        Some(day) if day.year() == today.year() => day.format("%A, %B %-d").to_string(),
+        Some(day) => day.format("%A, %B %-d, %Y").to_string(),
+    }
+}
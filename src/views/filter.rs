@@ -0,0 +1,764 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::views::{sort_by_due, task_subtitle};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use anyhow::{anyhow, bail};
+use chrono::{Days, Local, NaiveDate};
+use gtk::{
+    gio, glib, glib::clone, Button, CompositeTemplate, Label, ListBox, Stack, Widget,
+};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use tracing::error;
+
+/// The settings key the saved smart filters are stored in.
+const SMART_FILTERS_KEY: &str = "smart-filters";
+
+/// A query that is saved under a name and shown in the sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SmartFilter {
+    pub(crate) name: String,
+    pub(crate) query: String,
+}
+
+impl SmartFilter {
+    pub(crate) fn load_all(settings: &gio::Settings) -> Vec<Self> {
+        settings
+            .get::<Vec<(String, String)>>(SMART_FILTERS_KEY)
+            .into_iter()
+            .map(|(name, query)| Self { name, query })
+            .collect()
+    }
+
+    pub(crate) fn save_all(
+        settings: &gio::Settings,
+        smart_filters: &[Self],
+    ) -> anyhow::Result<()> {
+        let value: Vec<(String, String)> = smart_filters
+            .iter()
+            .map(|smart_filter| (smart_filter.name.clone(), smart_filter.query.clone()))
+            .collect();
+        settings.set_value(SMART_FILTERS_KEY, &value.to_variant())?;
+        Ok(())
+    }
+}
+
+/// A parsed query, that matches tasks for which all of its terms match.
+///
+/// Terms are separated by whitespace and can be negated with a leading `!` or `-`:
+/// - `done` matches completed tasks
+/// - `due:today`, `due:tomorrow`, `due:overdue`, `due:none` and `due:any`
+/// - `due:<7d`, `due:>=2w` or `due:2025-01-31`, compared with today and also taking
+///   `<`, `<=`, `>`, `>=` and `=`
+/// - `priority:>=2` with the same comparisons
+/// - `label:work` matches tasks whose content contains the tag `#work`
+/// - `collection:"Ops"` matches tasks of the collection with that title
+/// - any other word matches tasks whose content contains it
+///
+/// Values with whitespace are quoted, like `collection:"Side Projects"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Done,
+    Text(String),
+    Label(String),
+    Collection(String),
+    Priority(Comparison, u8),
+    Due(DueCondition),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DueCondition {
+    None,
+    Any,
+    Overdue,
+    Compare(Comparison, DueDate),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DueDate {
+    /// Days from today, resolved when matching so that saved queries move along
+    Relative(i64),
+    Absolute(NaiveDate),
+}
+
+impl DueDate {
+    /// The date, `None` if it is out of range.
+    fn resolve(&self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Relative(days) if *days < 0 => {
+                today.checked_sub_days(Days::new(days.unsigned_abs()))
+            }
+            Self::Relative(days) => {
+                today.checked_add_days(Days::new(days.unsigned_abs()))
+            }
+            Self::Absolute(date) => Some(*date),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    /// Splits off a leading comparison operator, which defaults to equality.
+    fn split(value: &str) -> (Self, &str) {
+        for (operator, comparison) in [
+            ("<=", Self::LessOrEqual),
+            (">=", Self::GreaterOrEqual),
+            ("<", Self::Less),
+            (">", Self::Greater),
+            ("=", Self::Equal),
+        ] {
+            if let Some(rest) = value.strip_prefix(operator) {
+                return (comparison, rest);
+            }
+        }
+        (Self::Equal, value)
+    }
+
+    fn holds<T: Ord>(&self, left: T, right: T) -> bool {
+        let ordering = left.cmp(&right);
+        match self {
+            Self::Less => ordering == Ordering::Less,
+            Self::LessOrEqual => ordering != Ordering::Greater,
+            Self::Equal => ordering == Ordering::Equal,
+            Self::GreaterOrEqual => ordering != Ordering::Less,
+            Self::Greater => ordering == Ordering::Greater,
+        }
+    }
+}
+
+/// A whitespace separated word of the query.
+struct Token {
+    /// The column of the first character, starting at 1
+    column: usize,
+    text: String,
+    /// Whether the whole token was quoted, which turns it into plain text
+    quoted: bool,
+}
+
+fn tokenize(query: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().enumerate().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut text = String::new();
+        let mut open_quote = None;
+        while let Some(&(i, c)) = chars.peek() {
+            if c == '"' {
+                open_quote = match open_quote {
+                    Some(_) => None,
+                    None => Some(i),
+                };
+            } else if c.is_whitespace() && open_quote.is_none() {
+                break;
+            } else {
+                text.push(c);
+            }
+            chars.next();
+        }
+        if let Some(i) = open_quote {
+            bail!("The quote at column {} is never closed", i + 1);
+        }
+        tokens.push(Token {
+            column: start + 1,
+            quoted: query.chars().nth(start) == Some('"'),
+            text,
+        });
+    }
+    Ok(tokens)
+}
+
+impl Query {
+    pub(crate) fn parse(query: &str) -> anyhow::Result<Self> {
+        let terms = tokenize(query)?
+            .into_iter()
+            .map(Term::parse)
+            .collect::<anyhow::Result<Vec<Term>>>()?;
+        Ok(Self { terms })
+    }
+
+    pub(crate) fn matches(
+        &self,
+        collection: &CollectionObject,
+        task_object: &TaskObject,
+        today: NaiveDate,
+    ) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(collection, task_object, today))
+    }
+
+    /// The matching tasks of every collection, ordered by their due date.
+    pub(crate) fn tasks(&self, todo: &RnTodo) -> Vec<(CollectionObject, TaskObject)> {
+        let today = Local::now().date_naive();
+        let mut tasks: Vec<(CollectionObject, TaskObject)> = todo
+            .all_tasks()
+            .into_iter()
+            .filter(|(collection, task_object)| {
+                self.matches(collection, task_object, today)
+            })
+            .collect();
+        sort_by_due(&mut tasks);
+        tasks
+    }
+
+    /// The number of tasks that match, without collecting and sorting them.
+    pub(crate) fn count(&self, todo: &RnTodo) -> usize {
+        let today = Local::now().date_naive();
+        todo.all_tasks()
+            .iter()
+            .filter(|(collection, task_object)| {
+                self.matches(collection, task_object, today)
+            })
+            .count()
+    }
+}
+
+impl Term {
+    fn parse(token: Token) -> anyhow::Result<Self> {
+        let Token {
+            column,
+            text,
+            quoted,
+        } = token;
+        if quoted {
+            return Ok(Self {
+                negated: false,
+                condition: Condition::Text(text.to_lowercase()),
+            });
+        }
+
+        let (negated, body) = match text.strip_prefix(['!', '-']) {
+            Some(body) if !body.is_empty() => (true, body),
+            _ => (false, text.as_str()),
+        };
+        let condition = match body.split_once(':') {
+            None if body.eq_ignore_ascii_case("done") => Condition::Done,
+            None => Condition::Text(body.to_lowercase()),
+            Some((field, "")) => {
+                bail!("`{field}:` at column {column} is missing a value")
+            }
+            Some((field, value)) => match field.to_lowercase().as_str() {
+                "due" => Condition::Due(
+                    parse_due(value)
+                        .map_err(|e| anyhow!("{e} in `{body}` at column {column}"))?,
+                ),
+                "priority" => {
+                    let (comparison, number) = Comparison::split(value);
+                    let priority = number.parse::<u8>().map_err(|_| {
+                        anyhow!(
+                            "`{number}` is not a priority in `{body}` at column {column}, expected a number like 2"
+                        )
+                    })?;
+                    Condition::Priority(comparison, priority)
+                }
+                "label" => Condition::Label(value.trim_start_matches('#').to_lowercase()),
+                "collection" => Condition::Collection(value.to_lowercase()),
+                _ => bail!(
+                    "Unknown field `{field}` at column {column}, expected due, priority, label or collection"
+                ),
+            },
+        };
+        Ok(Self { negated, condition })
+    }
+
+    fn matches(
+        &self,
+        collection: &CollectionObject,
+        task_object: &TaskObject,
+        today: NaiveDate,
+    ) -> bool {
+        let matches = match &self.condition {
+            Condition::Done => task_object.is_completed(),
+            Condition::Text(text) => {
+                task_object.content().to_lowercase().contains(text)
+            }
+            Condition::Label(label) => task_object.labels().contains(label),
+            Condition::Collection(title) => collection.title().to_lowercase() == *title,
+            Condition::Priority(comparison, priority) => {
+                comparison.holds(task_object.priority(), *priority)
+            }
+            Condition::Due(due_condition) => {
+                let due_date = task_object.due_date();
+                match due_condition {
+                    DueCondition::None => due_date.is_none(),
+                    DueCondition::Any => due_date.is_some(),
+                    DueCondition::Overdue => due_date.is_some_and(|date| date < today),
+                    DueCondition::Compare(comparison, date) => {
+                        due_date.zip(date.resolve(today)).is_some_and(
+                            |(due_date, date)| comparison.holds(due_date, date),
+                        )
+                    }
+                }
+            }
+        };
+        matches != self.negated
+    }
+}
+
+fn parse_due(value: &str) -> anyhow::Result<DueCondition> {
+    let keyword = match value.to_lowercase().as_str() {
+        "none" => Some(DueCondition::None),
+        "any" => Some(DueCondition::Any),
+        "overdue" => Some(DueCondition::Overdue),
+        "today" => Some(DueCondition::Compare(
+            Comparison::Equal,
+            DueDate::Relative(0),
+        )),
+        "tomorrow" => Some(DueCondition::Compare(
+            Comparison::Equal,
+            DueDate::Relative(1),
+        )),
+        _ => None,
+    };
+    if let Some(due_condition) = keyword {
+        return Ok(due_condition);
+    }
+
+    let (comparison, date) = Comparison::split(value);
+    if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return Ok(DueCondition::Compare(comparison, DueDate::Absolute(date)));
+    }
+    let days_per_unit = match date.chars().last() {
+        Some('d') => 1,
+        Some('w') => 7,
+        _ => bail!(
+            "`{date}` is not a date, expected today, tomorrow, overdue, none, any, a number of days or weeks like 7d or 2w, or a date like 2025-01-31"
+        ),
+    };
+    let count = date[..date.len() - 1].parse::<i64>().map_err(|_| {
+        anyhow!("`{date}` does not start with a number of days or weeks")
+    })?;
+    let due_date = count
+        .checked_mul(days_per_unit)
+        .map(DueDate::Relative)
+        .filter(|due_date| due_date.resolve(Local::now().date_naive()).is_some())
+        .ok_or_else(|| anyhow!("`{date}` is a date out of range"))?;
+    Ok(DueCondition::Compare(comparison, due_date))
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/filter.ui")]
+    pub(crate) struct RnFilterView {
+        pub(crate) todo: glib::WeakRef<RnTodo>,
+        pub(crate) appwindow: glib::WeakRef<RnAppWindow>,
+        /// The name the shown filter is saved under, `None` for a new filter
+        pub(crate) saved_name: RefCell<Option<String>>,
+
+        #[template_child]
+        pub(crate) name_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) query_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) error_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) save_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) delete_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) stack: TemplateChild<Stack>,
+        #[template_child]
+        pub(crate) task_list: TemplateChild<ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnFilterView {
+        const NAME: &'static str = "RnFilterView";
+        type Type = super::RnFilterView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnFilterView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnFilterView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnFilterView(ObjectSubclass<imp::RnFilterView>)
+        @extends Widget;
+}
+
+impl Default for RnFilterView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnFilterView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.imp()
+            .todo
+            .upgrade()
+            .expect("`todo` should be set in `init`.")
+    }
+
+    fn app_settings(&self) -> Option<gio::Settings> {
+        self.imp()
+            .appwindow
+            .upgrade()
+            .and_then(|appwindow| appwindow.app().app_settings())
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let todo = appwindow.todo();
+        imp.todo.set(Some(&todo));
+        imp.appwindow.set(Some(appwindow));
+
+        todo.connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        // Relative due dates might have moved since the last refresh
+        self.connect_map(|view| {
+            view.refresh();
+        });
+        imp.query_entry.connect_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.refresh();
+            }
+        ));
+        imp.query_entry.connect_entry_activated(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.save();
+            }
+        ));
+        imp.save_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.save();
+            }
+        ));
+        imp.delete_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.delete();
+            }
+        ));
+        self.show_filter(None);
+    }
+
+    /// Shows the tasks of a saved filter, or an empty query for a new filter.
+    pub(crate) fn show_filter(&self, smart_filter: Option<&SmartFilter>) {
+        let imp = self.imp();
+        imp.saved_name
+            .replace(smart_filter.map(|smart_filter| smart_filter.name.clone()));
+        imp.name_entry
+            .set_text(smart_filter.map_or("", |smart_filter| &smart_filter.name));
+        imp.query_entry
+            .set_text(smart_filter.map_or("", |smart_filter| &smart_filter.query));
+        imp.delete_button.set_visible(smart_filter.is_some());
+        self.refresh();
+    }
+
+    fn show_error(&self, error: Option<&str>) {
+        let imp = self.imp();
+        imp.error_label.set_label(error.unwrap_or_default());
+        imp.error_label.set_visible(error.is_some());
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let todo = self.todo();
+        imp.task_list.remove_all();
+
+        let tasks = match Query::parse(&imp.query_entry.text()) {
+            Ok(query) => {
+                self.show_error(None);
+                query.tasks(&todo)
+            }
+            Err(e) => {
+                self.show_error(Some(&e.to_string()));
+                Vec::new()
+            }
+        };
+        for (collection, task_object) in &tasks {
+            let row = todo.create_task_row(task_object);
+            row.set_subtitle(&task_subtitle(collection, task_object, true));
+            imp.task_list.append(&row);
+        }
+        imp.stack.set_visible_child_name(if tasks.is_empty() {
+            "empty"
+        } else {
+            "tasks"
+        });
+    }
+
+    fn save(&self) {
+        let imp = self.imp();
+        let name = imp.name_entry.text().trim().to_string();
+        let query = imp.query_entry.text().trim().to_string();
+
+        if name.is_empty() {
+            self.show_error(Some("Enter a name to save the filter"));
+            imp.name_entry.grab_focus();
+            return;
+        }
+        if let Err(e) = Query::parse(&query) {
+            self.show_error(Some(&e.to_string()));
+            return;
+        }
+        let Some(app_settings) = self.app_settings() else {
+            self.show_error(Some("Filters can't be saved without the settings schema"));
+            return;
+        };
+
+        // Replace the filter that is edited, or one with the same name
+        let saved_name = imp.saved_name.borrow().clone();
+        let mut smart_filters = SmartFilter::load_all(&app_settings);
+        let position = smart_filters.iter().position(|smart_filter| {
+            Some(&smart_filter.name) == saved_name.as_ref() || smart_filter.name == name
+        });
+        smart_filters.retain(|smart_filter| {
+            Some(&smart_filter.name) != saved_name.as_ref() && smart_filter.name != name
+        });
+        let smart_filter = SmartFilter { name, query };
+        match position {
+            Some(position) => smart_filters
+                .insert(position.min(smart_filters.len()), smart_filter.clone()),
+            None => smart_filters.push(smart_filter.clone()),
+        }
+
+        if let Err(e) = SmartFilter::save_all(&app_settings, &smart_filters) {
+            error!("Saving smart filters failed, Err: {e:?}");
+            self.show_error(Some("Saving the filter failed"));
+            return;
+        }
+        self.show_filter(Some(&smart_filter));
+    }
+
+    fn delete(&self) {
+        let Some(saved_name) = self.imp().saved_name.borrow().clone() else {
+            return;
+        };
+        let Some(app_settings) = self.app_settings() else {
+            return;
+        };
+
+        let mut smart_filters = SmartFilter::load_all(&app_settings);
+        smart_filters.retain(|smart_filter| smart_filter.name != saved_name);
+        if let Err(e) = SmartFilter::save_all(&app_settings, &smart_filters) {
+            error!("Deleting smart filter failed, Err: {e:?}");
+            self.show_error(Some("Deleting the filter failed"));
+            return;
+        }
+        self.show_filter(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(text: &str) -> anyhow::Result<Term> {
+        let mut tokens = tokenize(text)?;
+        assert_eq!(tokens.len(), 1, "`{text}` is one token");
+        Term::parse(tokens.remove(0))
+    }
+
+    fn condition(text: &str) -> Condition {
+        term(text).unwrap().condition
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace_outside_quotes() {
+        let tokens =
+            tokenize(r#"  done  collection:"Side Projects" "due:today""#).unwrap();
+        let texts: Vec<(&str, usize, bool)> = tokens
+            .iter()
+            .map(|token| (token.text.as_str(), token.column, token.quoted))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                ("done", 3, false),
+                ("collection:Side Projects", 9, false),
+                ("due:today", 36, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_rejects_unclosed_quotes() {
+        let Err(error) = tokenize(r#"done "side"#) else {
+            panic!("the quote is open");
+        };
+        assert_eq!(error.to_string(), "The quote at column 6 is never closed");
+    }
+
+    #[test]
+    fn parse_fields() {
+        assert_eq!(condition("done"), Condition::Done);
+        assert_eq!(condition("Milk"), Condition::Text(String::from("milk")));
+        assert_eq!(
+            condition("label:#Work"),
+            Condition::Label(String::from("work"))
+        );
+        assert_eq!(
+            condition(r#"collection:"Side Projects""#),
+            Condition::Collection(String::from("side projects"))
+        );
+        assert_eq!(
+            condition("priority:>=2"),
+            Condition::Priority(Comparison::GreaterOrEqual, 2)
+        );
+        // A quoted term is always text
+        assert_eq!(
+            condition(r#""done""#),
+            Condition::Text(String::from("done"))
+        );
+    }
+
+    #[test]
+    fn parse_negation() {
+        let negated = term("!done").unwrap();
+        assert!(negated.negated);
+        assert_eq!(negated.condition, Condition::Done);
+        assert!(term("-label:work").unwrap().negated);
+        // A lone `-` is text
+        assert_eq!(condition("-"), Condition::Text(String::from("-")));
+    }
+
+    #[test]
+    fn parse_due_conditions() {
+        assert_eq!(condition("due:none"), Condition::Due(DueCondition::None));
+        assert_eq!(
+            condition("due:Overdue"),
+            Condition::Due(DueCondition::Overdue)
+        );
+        assert_eq!(
+            condition("due:tomorrow"),
+            Condition::Due(DueCondition::Compare(
+                Comparison::Equal,
+                DueDate::Relative(1)
+            ))
+        );
+        assert_eq!(
+            condition("due:<7d"),
+            Condition::Due(DueCondition::Compare(
+                Comparison::Less,
+                DueDate::Relative(7)
+            ))
+        );
+        assert_eq!(
+            condition("due:>=-2w"),
+            Condition::Due(DueCondition::Compare(
+                Comparison::GreaterOrEqual,
+                DueDate::Relative(-14)
+            ))
+        );
+        assert_eq!(
+            condition("due:<=2025-01-31"),
+            Condition::Due(DueCondition::Compare(
+                Comparison::LessOrEqual,
+                DueDate::Absolute(NaiveDate::from_ymd_opt(2025, 1, 31).unwrap())
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            term("due:").unwrap_err().to_string(),
+            "`due:` at column 1 is missing a value"
+        );
+        assert!(term("due:soon")
+            .unwrap_err()
+            .to_string()
+            .starts_with("`soon` is not a date"));
+        assert!(term("priority:high")
+            .unwrap_err()
+            .to_string()
+            .starts_with("`high` is not a priority"));
+        assert!(term("color:red")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Unknown field `color`"));
+    }
+
+    #[test]
+    fn parse_due_out_of_range() {
+        for (query, date) in [
+            ("due:<9223372036854775807w", "9223372036854775807w"),
+            ("due:>-9223372036854775808d", "-9223372036854775808d"),
+            ("due:99999999999d", "99999999999d"),
+        ] {
+            assert_eq!(
+                term(query).unwrap_err().to_string(),
+                format!("`{date}` is a date out of range in `{query}` at column 1"),
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_out_of_range() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(
+            DueDate::Relative(-1).resolve(today),
+            NaiveDate::from_ymd_opt(2024, 12, 31)
+        );
+        assert_eq!(DueDate::Relative(i64::MAX).resolve(today), None);
+        assert_eq!(DueDate::Relative(i64::MIN).resolve(today), None);
+    }
+}
@@ -0,0 +1,491 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::{TaskDue, TaskObject};
+use crate::views::{
+    add_task_drag_source, add_task_drop_target, sort_by_due, task_subtitle,
+};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::{Days, Local, NaiveDate};
+use gtk::{
+    gio, glib, glib::clone, CompositeTemplate, Entry, Grid, Label, ListBox, SpinButton,
+    Widget,
+};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/matrix.ui")]
+    pub(crate) struct RnMatrixView {
+        pub(crate) appwindow: glib::WeakRef<RnAppWindow>,
+
+        #[template_child]
+        pub(crate) urgent_days_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub(crate) important_priority_spin: TemplateChild<SpinButton>,
+        #[template_child]
+        pub(crate) important_label_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub(crate) quadrants_grid: TemplateChild<Grid>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnMatrixView {
+        const NAME: &'static str = "RnMatrixView";
+        type Type = super::RnMatrixView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnMatrixView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnMatrixView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnMatrixView(ObjectSubclass<imp::RnMatrixView>)
+        @extends Widget;
+}
+
+impl Default for RnMatrixView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// When open tasks count as urgent and as important, as configured in the settings.
+#[derive(Debug, Clone)]
+struct Triage {
+    today: NaiveDate,
+    /// Tasks due within this many days from today are urgent, overdue tasks always are
+    urgent_days: u32,
+    /// Tasks with at least this priority are important, 0 disables it
+    important_priority: u8,
+    /// Tasks with this label are important, empty disables it
+    important_label: String,
+}
+
+impl Triage {
+    fn from_settings(settings: &gio::Settings) -> Self {
+        Self {
+            today: Local::now().date_naive(),
+            urgent_days: settings.uint("matrix-urgent-days"),
+            important_priority: u8::try_from(
+                settings.uint("matrix-important-priority"),
+            )
+            .unwrap_or(u8::MAX),
+            important_label: settings
+                .string("matrix-important-label")
+                .trim()
+                .trim_start_matches('#')
+                .to_lowercase(),
+        }
+    }
+
+    fn last_urgent_day(&self) -> NaiveDate {
+        self.today + Days::new(u64::from(self.urgent_days))
+    }
+
+    fn is_urgent(&self, task_object: &TaskObject) -> bool {
+        task_object
+            .due_date()
+            .is_some_and(|date| date <= self.last_urgent_day())
+    }
+
+    fn is_important(&self, task_object: &TaskObject) -> bool {
+        (self.important_priority > 0
+            && task_object.priority() >= self.important_priority)
+            || (!self.important_label.is_empty()
+                && task_object.labels().contains(&self.important_label))
+    }
+
+    fn quadrant(&self, task_object: &TaskObject) -> Quadrant {
+        Quadrant {
+            urgent: self.is_urgent(task_object),
+            important: self.is_important(task_object),
+        }
+    }
+
+    /// Changes the due date, priority and labels of the task so that it lands in the quadrant.
+    fn move_to(&self, task_object: &TaskObject, quadrant: Quadrant) {
+        if quadrant.urgent && !self.is_urgent(task_object) {
+            match task_object.due() {
+                Some(_) => task_object.reschedule(self.today),
+                None => task_object.set_due(Some(TaskDue::new(self.today, None))),
+            }
+        } else if !quadrant.urgent && self.is_urgent(task_object) {
+            task_object.reschedule(self.last_urgent_day() + Days::new(1));
+        }
+
+        if quadrant.important && !self.is_important(task_object) {
+            if self.important_priority > 0 {
+                task_object.set_priority(self.important_priority);
+            } else if !self.important_label.is_empty() {
+                task_object.add_label(&self.important_label);
+            }
+        } else if !quadrant.important && self.is_important(task_object) {
+            if self.important_priority > 0
+                && task_object.priority() >= self.important_priority
+            {
+                task_object.set_priority(self.important_priority - 1);
+            }
+            if !self.important_label.is_empty() {
+                task_object.remove_label(&self.important_label);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Quadrant {
+    urgent: bool,
+    important: bool,
+}
+
+impl Quadrant {
+    /// The quadrants in reading order, important tasks on top and urgent tasks left.
+    const ALL: [Self; 4] = [
+        Self {
+            urgent: true,
+            important: true,
+        },
+        Self {
+            urgent: false,
+            important: true,
+        },
+        Self {
+            urgent: true,
+            important: false,
+        },
+        Self {
+            urgent: false,
+            important: false,
+        },
+    ];
+
+    fn title(&self) -> &'static str {
+        match (self.urgent, self.important) {
+            (true, true) => "Do First",
+            (false, true) => "Schedule",
+            (true, false) => "Delegate",
+            (false, false) => "Eliminate",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match (self.urgent, self.important) {
+            (true, true) => "Urgent and important",
+            (false, true) => "Important, not urgent",
+            (true, false) => "Urgent, not important",
+            (false, false) => "Neither urgent nor important",
+        }
+    }
+
+    fn css_class(&self) -> &'static str {
+        match (self.urgent, self.important) {
+            (true, true) => "error",
+            (false, true) => "accent",
+            (true, false) => "warning",
+            (false, false) => "dim-label",
+        }
+    }
+}
+
+impl RnMatrixView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn appwindow(&self) -> RnAppWindow {
+        self.imp()
+            .appwindow
+            .upgrade()
+            .expect("`appwindow` should be set in `init`.")
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.appwindow().todo()
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        imp.appwindow.set(Some(appwindow));
+
+        appwindow.todo().connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        // The day might have changed since the last refresh
+        self.connect_map(|view| {
+            view.refresh();
+        });
+
+        if let Some(app_settings) = appwindow.app().app_settings() {
+            app_settings
+                .bind("matrix-urgent-days", &imp.urgent_days_spin.get(), "value")
+                .build();
+            app_settings
+                .bind(
+                    "matrix-important-priority",
+                    &imp.important_priority_spin.get(),
+                    "value",
+                )
+                .build();
+            app_settings
+                .bind(
+                    "matrix-important-label",
+                    &imp.important_label_entry.get(),
+                    "text",
+                )
+                .build();
+            app_settings.connect_changed(
+                None,
+                clone!(
+                    #[weak(rename_to = view)]
+                    self,
+                    move |_, key| {
+                        if key.starts_with("matrix-") {
+                            view.refresh();
+                        }
+                    }
+                ),
+            );
+        }
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        while let Some(child) = imp.quadrants_grid.first_child() {
+            imp.quadrants_grid.remove(&child);
+        }
+        let Some(app_settings) = self.appwindow().app().app_settings() else {
+            return;
+        };
+        let triage = Triage::from_settings(&app_settings);
+        let todo = self.todo();
+
+        let mut open_tasks: Vec<(CollectionObject, TaskObject)> = todo
+            .all_tasks()
+            .into_iter()
+            .filter(|(_, task_object)| !task_object.is_completed())
+            .collect();
+        sort_by_due(&mut open_tasks);
+
+        for (i, quadrant) in Quadrant::ALL.into_iter().enumerate() {
+            let tasks: Vec<&(CollectionObject, TaskObject)> = open_tasks
+                .iter()
+                .filter(|(_, task_object)| triage.quadrant(task_object) == quadrant)
+                .collect();
+            let quadrant_box = self.create_quadrant(&todo, &triage, quadrant, &tasks);
+            imp.quadrants_grid.attach(
+                &quadrant_box,
+                (i % 2) as i32,
+                (i / 2) as i32,
+                1,
+                1,
+            );
+        }
+    }
+
+    fn create_quadrant(
+        &self,
+        todo: &RnTodo,
+        triage: &Triage,
+        quadrant: Quadrant,
+        tasks: &[&(CollectionObject, TaskObject)],
+    ) -> gtk::Box {
+        let quadrant_box = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(6)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        quadrant_box.add_css_class("matrix_quadrant");
+
+        let header = gtk::Box::builder().spacing(6).build();
+        let title = Label::builder()
+            .label(quadrant.title())
+            .tooltip_text(quadrant.description())
+            .xalign(0.0)
+            .hexpand(true)
+            .build();
+        title.add_css_class("heading");
+        title.add_css_class(quadrant.css_class());
+        header.append(&title);
+        let count = Label::new(Some(&tasks.len().to_string()));
+        count.add_css_class("dim-label");
+        header.append(&count);
+        quadrant_box.append(&header);
+
+        let list = ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .valign(gtk::Align::Start)
+            .build();
+        list.add_css_class("boxed-list");
+        for (collection, task_object) in tasks {
+            let row = todo.create_task_row(task_object);
+            row.set_subtitle(&task_subtitle(collection, task_object, true));
+            add_task_drag_source(&row, task_object);
+            list.append(&row);
+        }
+        let scrolled_window = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        quadrant_box.append(&scrolled_window);
+
+        let triage = triage.clone();
+        add_task_drop_target(&quadrant_box, move |task_object| {
+            triage.move_to(&task_object, quadrant);
+        });
+        quadrant_box
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, 16).unwrap()
+    }
+
+    fn triage(important_priority: u8, important_label: &str) -> Triage {
+        Triage {
+            today: today(),
+            urgent_days: 2,
+            important_priority,
+            important_label: important_label.to_string(),
+        }
+    }
+
+    fn task(content: &str, due_in_days: Option<i64>, priority: u8) -> TaskObject {
+        let task_object = TaskObject::new(false, content.to_string());
+        task_object.set_due(
+            due_in_days.map(|days| {
+                TaskDue::new(today() + chrono::TimeDelta::days(days), None)
+            }),
+        );
+        task_object.set_priority(priority);
+        task_object
+    }
+
+    #[test]
+    fn urgent_up_to_the_last_urgent_day() {
+        let triage = triage(2, "important");
+        for (due_in_days, urgent) in [
+            (Some(-30), true),
+            (Some(0), true),
+            (Some(2), true),
+            (Some(3), false),
+            (None, false),
+        ] {
+            let task_object = task("Call vendor", due_in_days, 0);
+            assert_eq!(triage.is_urgent(&task_object), urgent, "{due_in_days:?}");
+        }
+
+        let triage = Triage {
+            urgent_days: 0,
+            ..triage
+        };
+        assert!(triage.is_urgent(&task("Call vendor", Some(0), 0)));
+        assert!(!triage.is_urgent(&task("Call vendor", Some(1), 0)));
+    }
+
+    #[test]
+    fn important_from_priority_or_label() {
+        let triage = triage(2, "important");
+        for (content, priority, important) in [
+            ("Call vendor", 1, false),
+            ("Call vendor", 2, true),
+            ("Call vendor", 3, true),
+            ("Call vendor #Important", 0, true),
+            ("Call vendor #importantish", 1, false),
+        ] {
+            let task_object = task(content, None, priority);
+            assert_eq!(
+                triage.is_important(&task_object),
+                important,
+                "{content} p{priority}"
+            );
+        }
+    }
+
+    #[test]
+    fn priority_zero_and_empty_label_are_ignored() {
+        let by_label = triage(0, "important");
+        assert!(!by_label.is_important(&task("Call vendor", None, 3)));
+        assert!(by_label.is_important(&task("Call vendor #important", None, 0)));
+
+        let by_priority = triage(2, "");
+        assert!(!by_priority.is_important(&task("Call vendor #important", None, 0)));
+
+        let never = triage(0, "");
+        assert!(!never.is_important(&task("Call vendor #important", None, 3)));
+    }
+
+    #[test]
+    fn moved_tasks_land_in_the_quadrant() {
+        for triage in [triage(2, "important"), triage(0, "important")] {
+            for from in Quadrant::ALL {
+                for to in Quadrant::ALL {
+                    let task_object = task("Call vendor", None, 0);
+                    triage.move_to(&task_object, from);
+                    assert_eq!(triage.quadrant(&task_object), from);
+                    triage.move_to(&task_object, to);
+                    assert_eq!(
+                        triage.quadrant(&task_object),
+                        to,
+                        "{from:?} to {to:?} with priority {}",
+                        triage.important_priority
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn moving_out_of_urgent_keeps_the_time() {
+        let triage = triage(2, "");
+        let task_object = task("Call vendor", Some(0), 0);
+        let time = chrono::NaiveTime::from_hms_opt(9, 30, 0);
+        task_object.set_due(Some(TaskDue::new(today(), time)));
+        triage.move_to(
+            &task_object,
+            Quadrant {
+                urgent: false,
+                important: false,
+            },
+        );
+        assert_eq!(
+            task_object.due(),
+            Some(TaskDue::new(today() + Days::new(3), time))
+        );
+    }
+}
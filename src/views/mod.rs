@@ -0,0 +1,119 @@
+// Modules
+mod calendar;
+mod completed;
+mod filter;
+mod matrix;
+mod scheduled;
+mod search;
+mod today;
+pub(crate) use calendar::RnCalendarView;
+pub(crate) use completed::RnCompletedView;
+pub(crate) use filter::{Query, RnFilterView, SmartFilter};
+pub(crate) use matrix::RnMatrixView;
+pub(crate) use scheduled::RnScheduledView;
+pub(crate) use search::RnSearchView;
+pub(crate) use today::RnTodayView;
+
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::{FilterType, RnTodo};
+use adw::prelude::*;
+use chrono::Local;
+use gtk::{gdk, glib, glib::clone};
+use std::cmp::Reverse;
+
+/// The number of tasks that the filter shows, as displayed on its sidebar tile.
+pub(crate) fn filter_count(todo: &RnTodo, filter_type: FilterType) -> usize {
+    let tasks = todo.all_tasks();
+    let open_tasks = tasks
+        .iter()
+        .filter(|(_, task_object)| !task_object.is_completed());
+    match filter_type {
+        FilterType::INBOX => open_tasks
+            .filter(|(collection, _)| collection.is_inbox())
+            .count(),
+        FilterType::TODAY => {
+            let today = Local::now().date_naive();
+            open_tasks
+                .filter(|(_, task_object)| {
+                    task_object.due_date().is_some_and(|date| date <= today)
+                })
+                .count()
+        }
+        FilterType::SCHEDULED => open_tasks
+            .filter(|(_, task_object)| task_object.due().is_some())
+            .count(),
+        FilterType::COMPLETED => tasks
+            .iter()
+            .filter(|(_, task_object)| task_object.is_completed())
+            .count(),
+        FilterType::PINBOARD | FilterType::LABELS => 0,
+    }
+}
+
+/// Orders tasks by day, then by time with untimed tasks last, then by descending priority.
+pub(crate) fn sort_by_due(tasks: &mut [(CollectionObject, TaskObject)]) {
+    tasks.sort_by_key(|(_, task_object)| {
+        let due = task_object.due();
+        (
+            due.map(|due| due.date),
+            due.and_then(|due| due.time).is_none(),
+            due.and_then(|due| due.time),
+            Reverse(task_object.priority()),
+        )
+    });
+}
+
+/// The subtitle of task rows in views that show tasks of all collections.
+pub(crate) fn task_subtitle(
+    collection: &CollectionObject,
+    task_object: &TaskObject,
+    with_date: bool,
+) -> String {
+    let mut subtitle = collection.title();
+    if let Some(due) = task_object.due() {
+        if with_date {
+            subtitle += &format!(" · {}", due.date.format("%b %-d"));
+        }
+        if let Some(time) = due.time {
+            subtitle += &format!(" · {}", time.format("%H:%M"));
+        }
+    }
+    subtitle
+}
+
+/// Makes the widget draggable onto drop targets added with [add_task_drop_target].
+pub(crate) fn add_task_drag_source(
+    widget: &impl IsA<gtk::Widget>,
+    task_object: &TaskObject,
+) {
+    let drag_source = gtk::DragSource::builder()
+        .actions(gdk::DragAction::MOVE)
+        .build();
+    drag_source.connect_prepare(clone!(
+        #[weak]
+        task_object,
+        #[upgrade_or]
+        None,
+        move |_, _, _| Some(gdk::ContentProvider::for_value(&task_object.to_value()))
+    ));
+    widget.add_controller(drag_source);
+}
+
+/// Calls `f` with tasks that are dropped onto the widget.
+pub(crate) fn add_task_drop_target<F: Fn(TaskObject) + 'static>(
+    widget: &impl IsA<gtk::Widget>,
+    f: F,
+) {
+    let drop_target =
+        gtk::DropTarget::new(TaskObject::static_type(), gdk::DragAction::MOVE);
+    drop_target.connect_drop(move |_, value, _, _| {
+        let Ok(task_object) = value.get::<TaskObject>() else {
+            return false;
+        };
+        f(task_object);
+        true
+    });
+    widget.add_controller(drop_target);
+}
@@ -0,0 +1,277 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::views::{
+    add_task_drag_source, add_task_drop_target, sort_by_due, task_subtitle,
+};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use gtk::{glib, glib::clone, Button, CompositeTemplate, Label, ListBox, Widget};
+use std::cell::Cell;
+
+/// How many months are added when scrolling further into the future.
+const MONTHS_STEP: u32 = 3;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/scheduled.ui")]
+    pub(crate) struct RnScheduledView {
+        pub(crate) todo: glib::WeakRef<RnTodo>,
+        pub(crate) months_shown: Cell<u32>,
+
+        #[template_child]
+        pub(crate) groups_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub(crate) more_button: TemplateChild<Button>,
+    }
+
+    impl Default for RnScheduledView {
+        fn default() -> Self {
+            Self {
+                todo: glib::WeakRef::default(),
+                months_shown: Cell::new(MONTHS_STEP),
+
+                groups_box: TemplateChild::<gtk::Box>::default(),
+                more_button: TemplateChild::<Button>::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnScheduledView {
+        const NAME: &'static str = "RnScheduledView";
+        type Type = super::RnScheduledView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnScheduledView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnScheduledView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnScheduledView(ObjectSubclass<imp::RnScheduledView>)
+        @extends Widget;
+}
+
+impl Default for RnScheduledView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A range of days that is listed under one header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayGroup {
+    Overdue,
+    Day(NaiveDate),
+    NextWeek(NaiveDate),
+    /// The rest of a month, starting at the date
+    Month(NaiveDate),
+}
+
+impl DayGroup {
+    /// The groups from today until the end of the month that is `months` away.
+    fn upcoming(today: NaiveDate, months: u32) -> Vec<Self> {
+        let mut groups = vec![Self::Overdue];
+        groups.extend((0..7).map(|i| Self::Day(today + Days::new(i))));
+        groups.push(Self::NextWeek(today + Days::new(7)));
+
+        let mut start = today + Days::new(14);
+        let end = today + Months::new(months);
+        while start <= end {
+            groups.push(Self::Month(start));
+            start = first_of_next_month(start);
+        }
+        groups
+    }
+
+    fn title(&self, today: NaiveDate) -> String {
+        match self {
+            Self::Overdue => String::from("Overdue"),
+            Self::Day(date) if *date == today => String::from("Today"),
+            Self::Day(date) if *date == today + Days::new(1) => {
+                String::from("Tomorrow")
+            }
+            Self::Day(date) => date.format("%A %-d").to_string(),
+            Self::NextWeek(_) => String::from("Next week"),
+            Self::Month(date) if date.year() == today.year() => {
+                date.format("%B").to_string()
+            }
+            Self::Month(date) => date.format("%B %Y").to_string(),
+        }
+    }
+
+    /// The first day of the group, that tasks dropped on its header are moved to.
+    fn start(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Overdue => None,
+            Self::Day(date) | Self::NextWeek(date) | Self::Month(date) => Some(*date),
+        }
+    }
+
+    fn contains(&self, date: NaiveDate, today: NaiveDate) -> bool {
+        match self {
+            Self::Overdue => date < today,
+            Self::Day(day) => date == *day,
+            Self::NextWeek(start) => date >= *start && date < *start + Days::new(7),
+            Self::Month(start) => date >= *start && date < first_of_next_month(*start),
+        }
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> NaiveDate {
+    let first = date
+        .with_day(1)
+        .expect("the first day exists in every month");
+    first + Months::new(1)
+}
+
+impl RnScheduledView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.imp()
+            .todo
+            .upgrade()
+            .expect("`todo` should be set in `init`.")
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let todo = appwindow.todo();
+        imp.todo.set(Some(&todo));
+
+        todo.connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        // The day might have changed since the last refresh
+        self.connect_map(|view| {
+            view.refresh();
+        });
+        imp.more_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                let imp = view.imp();
+                imp.months_shown.set(imp.months_shown.get() + MONTHS_STEP);
+                view.refresh();
+            }
+        ));
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let todo = self.todo();
+        let today = Local::now().date_naive();
+
+        while let Some(child) = imp.groups_box.first_child() {
+            imp.groups_box.remove(&child);
+        }
+
+        let mut dated_tasks: Vec<(CollectionObject, TaskObject)> = todo
+            .all_tasks()
+            .into_iter()
+            .filter(|(_, task_object)| {
+                !task_object.is_completed() && task_object.due().is_some()
+            })
+            .collect();
+        sort_by_due(&mut dated_tasks);
+
+        for group in DayGroup::upcoming(today, imp.months_shown.get()) {
+            let tasks: Vec<&(CollectionObject, TaskObject)> = dated_tasks
+                .iter()
+                .filter(|(_, task_object)| {
+                    task_object
+                        .due_date()
+                        .is_some_and(|date| group.contains(date, today))
+                })
+                .collect();
+            // Days are always listed so that tasks can be dropped onto them
+            if group == DayGroup::Overdue && tasks.is_empty() {
+                continue;
+            }
+            self.append_group(&todo, group, today, &tasks);
+        }
+    }
+
+    fn append_group(
+        &self,
+        todo: &RnTodo,
+        group: DayGroup,
+        today: NaiveDate,
+        tasks: &[&(CollectionObject, TaskObject)],
+    ) {
+        let imp = self.imp();
+        let with_date = !matches!(group, DayGroup::Day(_));
+
+        let header = gtk::Box::builder().spacing(6).build();
+        header.add_css_class("day_header");
+        let title = Label::builder()
+            .label(group.title(today))
+            .xalign(0.0)
+            .hexpand(true)
+            .build();
+        title.add_css_class("heading");
+        if group == DayGroup::Overdue {
+            title.add_css_class("error");
+        }
+        header.append(&title);
+        if !tasks.is_empty() {
+            let count = Label::new(Some(&tasks.len().to_string()));
+            count.add_css_class("dim-label");
+            header.append(&count);
+        }
+        if let Some(start) = group.start() {
+            add_task_drop_target(&header, move |task_object| {
+                task_object.reschedule(start);
+            });
+        }
+        imp.groups_box.append(&header);
+
+        if tasks.is_empty() {
+            return;
+        }
+        let list = ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        list.add_css_class("boxed-list");
+        for (collection, task_object) in tasks {
+            let row = todo.create_task_row(task_object);
+            row.set_subtitle(&task_subtitle(collection, task_object, with_date));
+            add_task_drag_source(&row, task_object);
+            list.append(&row);
+        }
+        imp.groups_box.append(&list);
+    }
+}
@@ -0,0 +1,309 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::search_index::{is_unspaced_script, tokenize, SearchIndex, SearchTarget};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*, ActionRow};
+use gtk::{
+    gio, glib, glib::clone, CompositeTemplate, ListBox, SearchEntry, Stack, Widget,
+};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+/// The most results that are listed, refining the query narrows them down.
+const RESULTS_LIMIT: usize = 200;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/search.ui")]
+    pub(crate) struct RnSearchView {
+        pub(crate) appwindow: glib::WeakRef<RnAppWindow>,
+        pub(crate) index: RefCell<SearchIndex>,
+        /// The handlers that mark the collections outdated in the index
+        pub(crate) collection_handlers:
+            RefCell<HashMap<CollectionObject, Vec<glib::SignalHandlerId>>>,
+        pub(crate) results: RefCell<Vec<SearchTarget>>,
+
+        #[template_child]
+        pub(crate) search_entry: TemplateChild<SearchEntry>,
+        #[template_child]
+        pub(crate) stack: TemplateChild<Stack>,
+        #[template_child]
+        pub(crate) results_list: TemplateChild<ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnSearchView {
+        const NAME: &'static str = "RnSearchView";
+        type Type = super::RnSearchView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnSearchView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnSearchView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnSearchView(ObjectSubclass<imp::RnSearchView>)
+        @extends Widget;
+}
+
+impl Default for RnSearchView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnSearchView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn appwindow(&self) -> RnAppWindow {
+        self.imp()
+            .appwindow
+            .upgrade()
+            .expect("`appwindow` should be set in `init`.")
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.appwindow().todo()
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        imp.appwindow.set(Some(appwindow));
+        imp.index.borrow_mut().invalidate_all();
+
+        let collections = appwindow.todo().collections();
+        self.watch_collections(&collections);
+        collections.connect_items_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |collections, _, _, _| {
+                view.imp().index.borrow_mut().invalidate_all();
+                view.watch_collections(collections);
+            }
+        ));
+        // The index is only synced while searching
+        appwindow.todo().connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        self.connect_map(|view| {
+            view.refresh();
+            view.imp().search_entry.grab_focus();
+        });
+        imp.search_entry.connect_search_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.refresh();
+            }
+        ));
+        imp.search_entry.connect_activate(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.open_result(0);
+            }
+        ));
+        imp.results_list.connect_row_activated(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_, row| {
+                view.open_result(row.index() as usize);
+            }
+        ));
+    }
+
+    /// Shows the search page with the query selected, so that typing replaces it.
+    pub(crate) fn start_search(&self) {
+        let appwindow = self.appwindow();
+        appwindow
+            .views_stack()
+            .set_visible_child_name("search_page");
+        let search_entry = self.imp().search_entry.get();
+        search_entry.grab_focus();
+        search_entry.select_region(0, -1);
+    }
+
+    /// Marks the collections outdated in the index when their title or tasks change,
+    /// and stops for the collections that were removed.
+    fn watch_collections(&self, collections: &gio::ListStore) {
+        let current: HashSet<CollectionObject> = collections
+            .iter::<CollectionObject>()
+            .filter_map(Result::ok)
+            .collect();
+        let mut handlers = self.imp().collection_handlers.borrow_mut();
+        handlers.retain(|collection, handler_ids| {
+            let keep = current.contains(collection);
+            if !keep {
+                for handler_id in handler_ids.drain(..) {
+                    collection.disconnect(handler_id);
+                }
+            }
+            keep
+        });
+        for collection in current {
+            if handlers.contains_key(&collection) {
+                continue;
+            }
+            let tasks_handler = collection.connect_tasks_changed(clone!(
+                #[weak(rename_to = view)]
+                self,
+                move |collection| {
+                    view.imp().index.borrow_mut().invalidate(collection);
+                }
+            ));
+            let title_handler = collection.connect_title_notify(clone!(
+                #[weak(rename_to = view)]
+                self,
+                move |collection| {
+                    view.imp().index.borrow_mut().invalidate(collection);
+                }
+            ));
+            handlers.insert(collection, vec![tasks_handler, title_handler]);
+        }
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        imp.index.borrow_mut().sync(&self.todo());
+
+        let query = imp.search_entry.text();
+        let terms = tokenize(&query);
+        let results = imp.index.borrow().search(&query, RESULTS_LIMIT);
+
+        imp.results_list.remove_all();
+        for target in &results {
+            imp.results_list.append(&result_row(target, &terms));
+        }
+        imp.stack.set_visible_child_name(if terms.is_empty() {
+            "hint"
+        } else if results.is_empty() {
+            "empty"
+        } else {
+            "results"
+        });
+        imp.results.replace(results);
+    }
+
+    /// Jumps to the task or collection of the result.
+    fn open_result(&self, index: usize) {
+        let Some(target) = self.imp().results.borrow().get(index).cloned() else {
+            return;
+        };
+        let appwindow = self.appwindow();
+        let todo = appwindow.todo();
+        appwindow.views_stack().set_visible_child_name("done_page");
+        match target {
+            SearchTarget::Task(collection, task_object) => {
+                todo.show_task(&collection, &task_object)
+            }
+            SearchTarget::Collection(collection) => todo.show_collection(&collection),
+        }
+    }
+}
+
+fn result_row(target: &SearchTarget, terms: &[String]) -> ActionRow {
+    let row = ActionRow::builder().activatable(true).build();
+    match target {
+        SearchTarget::Task(collection, task_object) => {
+            row.set_title(&highlight(&task_object.content(), terms));
+            let notes = task_object.notes();
+            let subtitle = match notes.lines().find(|line| has_match(line, terms)) {
+                Some(line) => format!(
+                    "{} · {}",
+                    glib::markup_escape_text(&collection.title()),
+                    highlight(line, terms)
+                ),
+                None => glib::markup_escape_text(&collection.title()).to_string(),
+            };
+            row.set_subtitle(&subtitle);
+            if task_object.is_completed() {
+                row.add_css_class("dim-label");
+            }
+        }
+        SearchTarget::Collection(collection) => {
+            row.set_title(&highlight(&collection.title(), terms));
+            row.set_subtitle("Collection");
+            row.add_prefix(&gtk::Image::from_icon_name(
+                "workspacelistentryicon-folder-symbolic",
+            ));
+        }
+    }
+    row
+}
+
+fn has_match(text: &str, terms: &[String]) -> bool {
+    tokenize(text)
+        .iter()
+        .any(|word| terms.iter().any(|term| word.starts_with(term.as_str())))
+}
+
+/// Escapes the text as markup, with the parts of words that match a term in bold.
+fn highlight(text: &str, terms: &[String]) -> String {
+    let mut markup = String::new();
+    let mut rest = text;
+
+    while let Some(first) = rest.chars().next() {
+        let is_word = first.is_alphanumeric();
+        let len = if is_unspaced_script(first) {
+            first.len_utf8()
+        } else if is_word {
+            rest.find(|c: char| !c.is_alphanumeric() || is_unspaced_script(c))
+                .unwrap_or(rest.len())
+        } else {
+            rest.find(char::is_alphanumeric).unwrap_or(rest.len())
+        };
+        let (segment, tail) = rest.split_at(len);
+        rest = tail;
+
+        let word = segment.to_lowercase();
+        let matched_chars = terms
+            .iter()
+            .filter(|term| is_word && word.starts_with(term.as_str()))
+            .map(|term| term.chars().count())
+            .max();
+        match matched_chars {
+            Some(matched_chars) => {
+                let split = segment
+                    .char_indices()
+                    .nth(matched_chars)
+                    .map_or(segment.len(), |(i, _)| i);
+                let (matched, unmatched) = segment.split_at(split);
+                markup += &format!("<b>{}</b>", glib::markup_escape_text(matched));
+                markup += &glib::markup_escape_text(unmatched);
+            }
+            None => markup += &glib::markup_escape_text(segment),
+        }
+    }
+    markup
+}
@@ -0,0 +1,188 @@
+// Imports
+use crate::collection_object::CollectionObject;
+use crate::task_object::TaskObject;
+use crate::views::{sort_by_due, task_subtitle};
+use crate::{RnAppWindow, RnTodo};
+use adw::{prelude::*, subclass::prelude::*};
+use chrono::Local;
+use gtk::{glib, glib::clone, Button, CompositeTemplate, ListBox, Stack, Widget};
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default, CompositeTemplate)]
+    #[template(resource = "/com/github/linruohan/mytool/ui/views/today.ui")]
+    pub(crate) struct RnTodayView {
+        pub(crate) todo: glib::WeakRef<RnTodo>,
+
+        #[template_child]
+        pub(crate) stack: TemplateChild<Stack>,
+        #[template_child]
+        pub(crate) overdue_group: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub(crate) overdue_list: TemplateChild<ListBox>,
+        #[template_child]
+        pub(crate) reschedule_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) today_group: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub(crate) today_list: TemplateChild<ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnTodayView {
+        const NAME: &'static str = "RnTodayView";
+        type Type = super::RnTodayView;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnTodayView {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnTodayView {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnTodayView(ObjectSubclass<imp::RnTodayView>)
+        @extends Widget;
+}
+
+impl Default for RnTodayView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Open tasks of all collections that are due today or overdue.
+#[derive(Debug, Default)]
+pub(crate) struct TodayTasks {
+    pub(crate) overdue: Vec<(CollectionObject, TaskObject)>,
+    pub(crate) today: Vec<(CollectionObject, TaskObject)>,
+}
+
+impl TodayTasks {
+    pub(crate) fn collect(todo: &RnTodo) -> Self {
+        let today = Local::now().date_naive();
+        let mut tasks = Self::default();
+
+        for (collection, task_object) in todo.all_tasks() {
+            if task_object.is_completed() {
+                continue;
+            }
+            match task_object.due_date() {
+                Some(date) if date == today => {
+                    tasks.today.push((collection, task_object))
+                }
+                Some(date) if date < today => {
+                    tasks.overdue.push((collection, task_object))
+                }
+                _ => {}
+            }
+        }
+
+        sort_by_due(&mut tasks.overdue);
+        sort_by_due(&mut tasks.today);
+        tasks
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.overdue.len() + self.today.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RnTodayView {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn todo(&self) -> RnTodo {
+        self.imp()
+            .todo
+            .upgrade()
+            .expect("`todo` should be set in `init`.")
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let todo = appwindow.todo();
+        imp.todo.set(Some(&todo));
+
+        todo.connect_tasks_changed(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                if view.is_mapped() {
+                    view.refresh();
+                }
+            }
+        ));
+        // The day might have changed since the last refresh
+        self.connect_map(|view| {
+            view.refresh();
+        });
+        imp.reschedule_button.connect_clicked(clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| {
+                view.reschedule_overdue_to_today();
+            }
+        ));
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let todo = self.todo();
+        let tasks = TodayTasks::collect(&todo);
+
+        imp.overdue_list.remove_all();
+        for (collection, task_object) in &tasks.overdue {
+            let row = todo.create_task_row(task_object);
+            row.set_subtitle(&task_subtitle(collection, task_object, true));
+            imp.overdue_list.append(&row);
+        }
+        imp.today_list.remove_all();
+        for (collection, task_object) in &tasks.today {
+            let row = todo.create_task_row(task_object);
+            row.set_subtitle(&task_subtitle(collection, task_object, false));
+            imp.today_list.append(&row);
+        }
+
+        imp.overdue_group.set_visible(!tasks.overdue.is_empty());
+        imp.today_group.set_visible(!tasks.today.is_empty());
+        imp.stack.set_visible_child_name(if tasks.is_empty() {
+            "empty"
+        } else {
+            "tasks"
+        });
+    }
+
+    /// Moves all overdue tasks to today, keeping their time.
+    fn reschedule_overdue_to_today(&self) {
+        let today = Local::now().date_naive();
+        for (_, task_object) in TodayTasks::collect(&self.todo()).overdue {
+            task_object.reschedule(today);
+        }
+    }
+}